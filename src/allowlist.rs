@@ -0,0 +1,51 @@
+//! Commands the user has allowed binds to run on this computer.
+//!
+//! These are kept in their own file rather than the config, so a shared config file can't run any command
+//! without the person using it allowing it first.
+
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::CommandBindAction;
+
+/// Commands which are allowed to be run
+#[derive(Default, Serialize, Deserialize)]
+pub struct CommandAllowlist {
+    commands: Vec<CommandBindAction>,
+}
+
+impl CommandAllowlist {
+    /// Read the allowlist from a file, or start with nothing allowed if it can't be read
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the allowlist to a file
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(path.parent().expect("invalid path"))?;
+        fs::write(path, serde_json::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    /// Is the command allowed to be run?
+    pub fn is_allowed(&self, command: &CommandBindAction) -> bool {
+        self.commands.iter().any(|x| x.runs_same_as(command))
+    }
+
+    /// Allow the command to be run, if it isn't already
+    pub fn allow(&mut self, command: &CommandBindAction) {
+        if !self.is_allowed(command) {
+            self.commands.push(command.clone());
+        }
+    }
+
+    /// Stop the command being allowed to run
+    pub fn disallow(&mut self, command: &CommandBindAction) {
+        self.commands.retain(|x| !x.runs_same_as(command));
+    }
+}
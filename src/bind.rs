@@ -1,18 +1,79 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
 use anyhow::anyhow;
 use enigo::{Enigo, KeyboardControllable, MouseControllable};
 
-use crate::{config::Bind, note::Note};
+use crate::{
+    clipboard,
+    config::{AbsolutePos2D, Bind, BindAction, ClipboardBindAction, MouseButton},
+    held::{HeldInput, HeldInputs},
+    http,
+    key::{Key, KeyCombo, Modifier, NamedKey},
+    monitor::Monitors,
+    note::Note,
+    osc::OscSender,
+    template::{fill_placeholder, fill_template},
+    text::{parse_text, TextPart},
+};
+
+/// How long after pasting what was on the clipboard before is put back
+const CLIPBOARD_RESTORE_DELAY: Duration = Duration::from_millis(500);
+
+/// Precise scrolling which hasn't added up to a whole line yet, in hundredths of a line right and down.
+///
+/// Clones share the same remainder, as every executor scrolls the same mouse.
+#[derive(Clone, Default)]
+pub struct ScrollRemainder {
+    remainder: Arc<Mutex<(i32, i32)>>,
+}
+
+impl ScrollRemainder {
+    /// Add hundredths of a line to scroll right and down, returning how many whole lines to scroll now
+    fn add(&self, x: i32, y: i32) -> (i32, i32) {
+        let mut remainder = self.remainder.lock().unwrap();
+
+        remainder.0 += x;
+        remainder.1 += y;
+        let lines = (remainder.0 / 100, remainder.1 / 100);
+        remainder.0 %= 100;
+        remainder.1 %= 100;
+
+        lines
+    }
+}
 
 /// Executes binds
 pub struct Executor {
     runtime: Enigo,
+    /// What's being held down, shared with every other executor
+    held: HeldInputs,
+    /// Precise scrolling left over, shared with every other executor
+    scroll: ScrollRemainder,
+    /// The monitors connected, shared with every other executor
+    monitors: Monitors,
+    /// Sends OSC from the same socket as every other executor
+    osc: OscSender,
 }
 
 impl Executor {
-    /// Creates a new Executor using Enigo
-    pub fn new() -> Self {
+    /// Creates a new Executor using Enigo, keeping track of what it holds down, scrolls, the monitors it can move to
+    /// and where it sends OSC from in the given shared state
+    pub fn new(
+        held: &HeldInputs,
+        scroll: &ScrollRemainder,
+        monitors: &Monitors,
+        osc: &OscSender,
+    ) -> Self {
         Self {
             runtime: Enigo::default(),
+            held: held.clone(),
+            scroll: scroll.clone(),
+            monitors: monitors.clone(),
+            osc: osc.clone(),
         }
     }
 
@@ -20,65 +81,146 @@ impl Executor {
     pub fn execute(
         &mut self,
         bind: &Bind,
-        vel: u8,
-        note: &Note,
+        event: &NoteEvent,
+        state: &BindExecuteState,
+    ) -> anyhow::Result<()> {
+        self.execute_action(&bind.action, event, state)?;
+
+        if let BindExecuteState::Release = state {
+            self.execute_release_action(bind, event)?;
+        }
+
+        Ok(())
+    }
+
+    /// Execute a bind's release action all at once, if it has one
+    pub fn execute_release_action(&mut self, bind: &Bind, event: &NoteEvent) -> anyhow::Result<()> {
+        if let Some(action) = &bind.release_action {
+            self.execute_action(action, event, &BindExecuteState::Begin)?;
+            self.execute_action(action, event, &BindExecuteState::Release)?;
+        }
+
+        Ok(())
+    }
+
+    /// Put a clipboard action's text on the clipboard in the background, then paste it and put back what was there
+    /// before if asked to.
+    ///
+    /// The clipboard programs can be slow to run, so this is done on its own thread like HTTP requests are, with
+    /// problems only logged
+    fn clipboard(&self, action: &ClipboardBindAction, event: &NoteEvent) {
+        let action = action.clone();
+        let text = fill_template(&action.text, event);
+        let event = event.clone();
+        let (held, scroll, monitors, osc) = (
+            self.held.clone(),
+            self.scroll.clone(),
+            self.monitors.clone(),
+            self.osc.clone(),
+        );
+
+        thread::spawn(move || {
+            // Enigo can't be sent between threads, so pasting is done by an executor of the thread's own
+            let mut executor = Executor::new(&held, &scroll, &monitors, &osc);
+
+            if let Err(e) = executor.paste_clipboard(&action, &text, &event) {
+                eprintln!("Couldn't use the clipboard: {e:#}");
+            }
+        });
+    }
+
+    /// Put the given text on the clipboard, then paste it and put back what was there before if the action asks to.
+    ///
+    /// If there was no text on the clipboard before, such as when an image had been copied, nothing is put back
+    fn paste_clipboard(
+        &mut self,
+        action: &ClipboardBindAction,
+        text: &str,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        // What was on the clipboard is only needed if it's to be put back
+        let previous = if action.paste && action.restore {
+            clipboard::get()?
+        } else {
+            None
+        };
+
+        clipboard::set(text)?;
+
+        if !action.paste {
+            return Ok(());
+        }
+
+        // Paste is Command+V on macOS, and Ctrl+V everywhere else
+        let paste = KeyCombo {
+            modifiers: vec![if cfg!(target_os = "macos") {
+                Modifier::Meta
+            } else {
+                Modifier::Control
+            }],
+            key: Key::Char('v'),
+        };
+        self.key_down(&paste, &event.note);
+        self.key_up(&paste, &event.note);
+
+        if let Some(previous) = previous {
+            // Programs read the clipboard in their own time after being told to paste, so it's put back a little later
+            thread::sleep(CLIPBOARD_RESTORE_DELAY);
+
+            if let Err(e) = clipboard::set(&previous) {
+                eprintln!("Couldn't put back what was on the clipboard: {e:#}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Execute a single action, such as a bind's action or a step of a macro.
+    ///
+    /// Problems which only affect this action, such as a missing program or unplugged monitor, are logged rather than
+    /// returned, as an error here stops the state and with it every other bind
+    pub fn execute_action(
+        &mut self,
+        action: &BindAction,
+        event: &NoteEvent,
         state: &BindExecuteState,
     ) -> anyhow::Result<()> {
-        match &bind.action {
+        match action {
             crate::config::BindAction::Debug => {
-                println!("Bind executed! state: {state:?}, note: {note}, velocity: {vel}");
+                println!(
+                    "Bind executed! state: {state:?}, note: {}, velocity: {}, channel: {}",
+                    event.note,
+                    event.vel,
+                    event.channel + 1
+                );
             }
             crate::config::BindAction::PressKey(param) => {
                 if let BindExecuteState::Begin = state {
-                    self.runtime.key_click(enigo::Key::Layout(
-                        param
-                            .key
-                            .chars()
-                            .next()
-                            .ok_or(anyhow!("invalid keyboard key in bind"))?,
-                    ));
-                }
-            }
-            crate::config::BindAction::HoldKey(param) => {
-                let key = enigo::Key::Layout(
-                    param
-                        .key
-                        .chars()
-                        .next()
-                        .ok_or(anyhow!("invalid keyboard key in bind"))?,
-                );
-                match state {
-                    BindExecuteState::Begin => {
-                        self.runtime.key_down(key);
-                    }
-                    BindExecuteState::Release => {
-                        self.runtime.key_up(key);
-                    }
+                    self.key_down(&param.key, &event.note);
+                    self.key_up(&param.key, &event.note);
                 }
             }
+            crate::config::BindAction::HoldKey(param) => match state {
+                BindExecuteState::Begin => {
+                    self.key_down(&param.key, &event.note);
+                }
+                BindExecuteState::Release => {
+                    self.key_up(&param.key, &event.note);
+                }
+            },
             crate::config::BindAction::Click(param) => {
                 if let BindExecuteState::Begin = state {
-                    self.runtime.mouse_click(match param {
-                        crate::config::MouseButton::Left => enigo::MouseButton::Left,
-                        crate::config::MouseButton::Right => enigo::MouseButton::Right,
-                        crate::config::MouseButton::Middle => enigo::MouseButton::Middle,
-                    });
+                    // Pressed and released separately, so a click doesn't break another note's hold of the button
+                    self.mouse_down(param, &event.note);
+                    self.mouse_up(param, &event.note);
                 }
             }
             crate::config::BindAction::HoldClick(param) => match state {
                 BindExecuteState::Begin => {
-                    self.runtime.mouse_down(match param {
-                        crate::config::MouseButton::Left => enigo::MouseButton::Left,
-                        crate::config::MouseButton::Right => enigo::MouseButton::Right,
-                        crate::config::MouseButton::Middle => enigo::MouseButton::Middle,
-                    });
+                    self.mouse_down(param, &event.note);
                 }
                 BindExecuteState::Release => {
-                    self.runtime.mouse_up(match param {
-                        crate::config::MouseButton::Left => enigo::MouseButton::Left,
-                        crate::config::MouseButton::Right => enigo::MouseButton::Right,
-                        crate::config::MouseButton::Middle => enigo::MouseButton::Middle,
-                    });
+                    self.mouse_up(param, &event.note);
                 }
             },
             crate::config::BindAction::MoveMouseRelative(param) => {
@@ -88,35 +230,392 @@ impl Executor {
             }
             crate::config::BindAction::MoveMouseAbsolute(param) => {
                 if let BindExecuteState::Begin = state {
-                    self.runtime.mouse_move_to(param.x, param.y);
+                    if let Some((x, y)) = self.locate(param) {
+                        self.runtime.mouse_move_to(x, y);
+                    }
                 }
             }
             crate::config::BindAction::Scroll(param) => {
                 if let BindExecuteState::Begin = state {
-                    let scroll_x = match param.direction {
+                    let mut scroll_x = match param.direction {
                         crate::config::ScrollDirection::Right => param.amount,
                         crate::config::ScrollDirection::Left => -param.amount,
                         _ => 0,
                     };
-                    let scroll_y = match param.direction {
+                    let mut scroll_y = match param.direction {
                         crate::config::ScrollDirection::Down => param.amount,
                         crate::config::ScrollDirection::Up => -param.amount,
                         _ => 0,
                     };
 
+                    // Only whole lines can be scrolled, so the rest is kept until there's enough for another
+                    if param.precise {
+                        (scroll_x, scroll_y) = self.scroll.add(scroll_x, scroll_y);
+                    }
+
                     self.runtime.mouse_scroll_x(scroll_x);
                     self.runtime.mouse_scroll_y(scroll_y);
                 }
             }
             crate::config::BindAction::Text(param) => {
                 if let BindExecuteState::Begin = state {
-                    self.runtime.key_sequence(&param.text);
+                    // Text written by hand in the config file might not make sense, such as a `{` which is never
+                    // closed, in which case none of it is typed rather than typing up to the mistake
+                    let parts = match parse_text(&param.text) {
+                        Ok(parts) => parts,
+                        Err(e) => {
+                            eprintln!("Couldn't type `{}`: {e}", param.text);
+                            return Ok(());
+                        }
+                    };
+
+                    for part in parts {
+                        match part {
+                            TextPart::Text(text) => self.runtime.key_sequence(&text),
+                            TextPart::Placeholder(name) => {
+                                self.runtime.key_sequence(&fill_placeholder(&name, event));
+                            }
+                            TextPart::Key(key) => {
+                                self.key_down(&key, &event.note);
+                                self.key_up(&key, &event.note);
+                            }
+                        }
+                    }
                 }
             }
-        };
+            crate::config::BindAction::MoveMouseContinuous(param) => {
+                // Continuous movement is run by the state while the note is held, so this only moves once
+                self.execute_action(
+                    &BindAction::MoveMouseRelative(param.step.clone()),
+                    event,
+                    state,
+                )?;
+            }
+            crate::config::BindAction::ScrollContinuous(param) => {
+                // Continuous scrolling is run by the state while the note is held, so this only scrolls once
+                self.execute_action(&BindAction::Scroll(param.step.clone()), event, state)?;
+            }
+            crate::config::BindAction::SendOsc(param) => {
+                if let BindExecuteState::Begin = state {
+                    // OSC is sent over UDP, so a receiver which isn't listening goes unnoticed, and only a host which
+                    // can't be found or a message which can't be sent is logged
+                    if let Err(e) = self.osc.send(param, event) {
+                        eprintln!("Couldn't send OSC to `{}`: {e:#}", param.address);
+                    }
+                }
+            }
+            crate::config::BindAction::Http(param) => {
+                if let BindExecuteState::Begin = state {
+                    http::send(param, event);
+                }
+            }
+            crate::config::BindAction::Clipboard(param) => {
+                if let BindExecuteState::Begin = state {
+                    self.clipboard(param, event);
+                }
+            }
+            crate::config::BindAction::ClickAt(param) => {
+                if let BindExecuteState::Begin = state {
+                    let (x, y) = self.runtime.mouse_location();
+                    let Some((to_x, to_y)) = self.locate(&param.position) else {
+                        return Ok(());
+                    };
+
+                    self.runtime.mouse_move_to(to_x, to_y);
+                    self.mouse_down(&param.button, &event.note);
+                    self.mouse_up(&param.button, &event.note);
+
+                    if param.return_cursor {
+                        self.runtime.mouse_move_to(x, y);
+                    }
+                }
+            }
+            crate::config::BindAction::Drag(param) => {
+                // Gliding is run by the state over time, so this drags straight from one end to the other
+                if let BindExecuteState::Begin = state {
+                    let (Some(from), Some(to)) = (self.locate(&param.from), self.locate(&param.to))
+                    else {
+                        return Ok(());
+                    };
+
+                    self.runtime.mouse_move_to(from.0, from.1);
+                    self.mouse_down(&param.button, &event.note);
+                    self.runtime.mouse_move_to(to.0, to.1);
+                    self.mouse_up(&param.button, &event.note);
+                }
+            }
+            crate::config::BindAction::GlideMouseRelative(param) => {
+                // Gliding is run by the state over time, so this moves straight there
+                self.execute_action(
+                    &BindAction::MoveMouseRelative(param.offset.clone()),
+                    event,
+                    state,
+                )?;
+            }
+            crate::config::BindAction::GlideMouseAbsolute(param) => {
+                // Gliding is run by the state over time, so this moves straight there
+                self.execute_action(
+                    &BindAction::MoveMouseAbsolute(param.position.clone()),
+                    event,
+                    state,
+                )?;
+            }
+            crate::config::BindAction::Macro(_)
+            | crate::config::BindAction::Command(_)
+            | crate::config::BindAction::SendMidi(_)
+            | crate::config::BindAction::Cycle(_)
+            | crate::config::BindAction::Random(_) => {
+                // Macros take time to perform, so they're run step by step by the state instead. Programs keep
+                // running after they're started, and MIDI is sent through connections, so the state keeps track of them too.
+                // Cycles and random actions have to remember what they've chosen, so the state picks for them
+            }
+        }
 
         Ok(())
     }
+
+    /// Returns where the mouse cursor is on the screen
+    pub fn mouse_location(&self) -> (i32, i32) {
+        self.runtime.mouse_location()
+    }
+
+    /// Returns where a position is on the screen in pixels.
+    ///
+    /// If it can't be found, such as when its monitor has been unplugged, this is logged and `None` is returned, so the
+    /// mouse is left where it is rather than moved onto the wrong monitor
+    pub fn locate(&self, position: &AbsolutePos2D) -> Option<(i32, i32)> {
+        self.monitors
+            .locate(
+                position,
+                self.runtime.mouse_location(),
+                self.runtime.main_display_size(),
+            )
+            .map_err(|e| eprintln!("Couldn't find where to move the mouse to: {e}"))
+            .ok()
+    }
+
+    /// List the connected monitors again, in case they've changed since they were last listed
+    pub fn refresh_monitors(&self) {
+        self.monitors.refresh(self.runtime.main_display_size());
+    }
+
+    /// Release every key and mouse button being held, whatever is holding them, most recently pressed first.
+    ///
+    /// As they're no longer tracked once this starts, any which can't be released don't stop the rest being released,
+    /// and are returned together as one error at the end
+    pub fn release_everything(&mut self) -> anyhow::Result<()> {
+        let mut errors = vec![];
+
+        for input in self.held.take_all().into_iter().rev() {
+            let released = match &input {
+                HeldInput::Key(key) => enigo_key(key).map(|x| self.runtime.key_up(x)),
+                HeldInput::MouseButton(button) => {
+                    enigo_mouse_button(button).map(|x| self.runtime.mouse_up(x))
+                }
+            };
+
+            if let Err(e) = released {
+                errors.push(format!("{input}: {e}"));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(anyhow!("couldn't release {}", errors.join(", ")));
+        }
+
+        Ok(())
+    }
+
+    /// Press down a key's modifiers, then the key itself, on behalf of the given note.
+    ///
+    /// If any of them can't be pressed on this platform, that's logged and nothing is pressed, rather than leaving
+    /// the modifiers held down without the key
+    fn key_down(&mut self, combo: &KeyCombo, note: &Note) {
+        let keys = match enigo_keys(combo) {
+            Ok(keys) => keys,
+            Err(e) => {
+                eprintln!("Couldn't press {combo}: {e}");
+                return;
+            }
+        };
+
+        for (key, enigo_key) in keys {
+            if self.held.press(HeldInput::Key(key), note) {
+                self.runtime.key_down(enigo_key);
+            }
+        }
+    }
+
+    /// Release a key, then its modifiers in the opposite order they were pressed, on behalf of the given note.
+    ///
+    /// Keys which can't be pressed on this platform were never pressed, so there's nothing to release
+    fn key_up(&mut self, combo: &KeyCombo, note: &Note) {
+        let Ok(keys) = enigo_keys(combo) else {
+            return;
+        };
+
+        for (key, enigo_key) in keys.into_iter().rev() {
+            if self.held.release(&HeldInput::Key(key), note) {
+                self.runtime.key_up(enigo_key);
+            }
+        }
+    }
+
+    /// Press down a mouse button, unless it's already held.
+    ///
+    /// If it can't be pressed on this platform, that's logged and it's skipped
+    fn mouse_down(&mut self, button: &MouseButton, note: &Note) {
+        let input = HeldInput::MouseButton(button.clone());
+
+        let enigo_button = match enigo_mouse_button(button) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Couldn't press the {input}: {e}");
+                return;
+            }
+        };
+
+        if self.held.press(input, note) {
+            self.runtime.mouse_down(enigo_button);
+        }
+    }
+
+    /// Release a mouse button, unless another note is still holding it.
+    ///
+    /// Buttons which can't be pressed on this platform were never pressed, so there's nothing to release
+    fn mouse_up(&mut self, button: &MouseButton, note: &Note) {
+        let Ok(enigo_button) = enigo_mouse_button(button) else {
+            return;
+        };
+
+        if self
+            .held
+            .release(&HeldInput::MouseButton(button.clone()), note)
+        {
+            self.runtime.mouse_up(enigo_button);
+        }
+    }
+}
+
+/// Convert a mouse button into the equivalent Enigo mouse button, if it can be pressed on this platform
+// Every button can be pressed on some platforms
+#[cfg_attr(
+    any(target_os = "windows", target_os = "linux"),
+    allow(clippy::unnecessary_wraps)
+)]
+fn enigo_mouse_button(button: &MouseButton) -> anyhow::Result<enigo::MouseButton> {
+    Ok(match button {
+        MouseButton::Left => enigo::MouseButton::Left,
+        MouseButton::Right => enigo::MouseButton::Right,
+        MouseButton::Middle => enigo::MouseButton::Middle,
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
+        MouseButton::Back => enigo::MouseButton::Back,
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
+        MouseButton::Forward => enigo::MouseButton::Forward,
+        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+        MouseButton::Back | MouseButton::Forward => {
+            anyhow::bail!("the back and forward mouse buttons can't be pressed on this platform")
+        }
+    })
+}
+
+/// Convert a key combo into each key to press, modifiers first, along with the equivalent Enigo keys, if every one
+/// of them can be pressed on this platform
+fn enigo_keys(combo: &KeyCombo) -> anyhow::Result<Vec<(Key, enigo::Key)>> {
+    combo
+        .modifiers
+        .iter()
+        .map(|x| Key::Named(x.key()))
+        .chain([combo.key.clone()])
+        .map(|x| Ok((x.clone(), enigo_key(&x)?)))
+        .collect()
+}
+
+/// Convert a key into the equivalent Enigo key, if it can be pressed on this platform
+fn enigo_key(key: &Key) -> anyhow::Result<enigo::Key> {
+    use enigo::Key as K;
+
+    let unsupported = || anyhow!("the key {key} can't be pressed on this platform");
+
+    Ok(match key {
+        Key::Char(x) => K::Layout(*x),
+        Key::Raw(x) => K::Raw(*x),
+        Key::Function(x) => match x {
+            1 => K::F1,
+            2 => K::F2,
+            3 => K::F3,
+            4 => K::F4,
+            5 => K::F5,
+            6 => K::F6,
+            7 => K::F7,
+            8 => K::F8,
+            9 => K::F9,
+            10 => K::F10,
+            11 => K::F11,
+            12 => K::F12,
+            13 => K::F13,
+            14 => K::F14,
+            15 => K::F15,
+            16 => K::F16,
+            17 => K::F17,
+            18 => K::F18,
+            19 => K::F19,
+            20 => K::F20,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            21 => K::F21,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            22 => K::F22,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            23 => K::F23,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            24 => K::F24,
+            _ => return Err(unsupported()),
+        },
+        Key::Named(x) => match x {
+            NamedKey::Return => K::Return,
+            NamedKey::Escape => K::Escape,
+            NamedKey::Tab => K::Tab,
+            NamedKey::Space => K::Space,
+            NamedKey::Backspace => K::Backspace,
+            NamedKey::Delete => K::Delete,
+            NamedKey::Home => K::Home,
+            NamedKey::End => K::End,
+            NamedKey::PageUp => K::PageUp,
+            NamedKey::PageDown => K::PageDown,
+            NamedKey::Up => K::UpArrow,
+            NamedKey::Down => K::DownArrow,
+            NamedKey::Left => K::LeftArrow,
+            NamedKey::Right => K::RightArrow,
+            NamedKey::Control => K::Control,
+            NamedKey::Shift => K::Shift,
+            NamedKey::Alt => K::Alt,
+            NamedKey::Meta => K::Meta,
+            NamedKey::CapsLock => K::CapsLock,
+            NamedKey::VolumeUp => K::VolumeUp,
+            NamedKey::VolumeDown => K::VolumeDown,
+            NamedKey::VolumeMute => K::VolumeMute,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            NamedKey::Insert => K::Insert,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            NamedKey::NumLock => K::Numlock,
+            #[cfg(target_os = "linux")]
+            NamedKey::ScrollLock => K::ScrollLock,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            NamedKey::PrintScreen => K::Print,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            NamedKey::Pause => K::Pause,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            NamedKey::MediaPlayPause => K::MediaPlayPause,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            NamedKey::MediaStop => K::MediaStop,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            NamedKey::MediaNext => K::MediaNextTrack,
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            NamedKey::MediaPrevious => K::MediaPrevTrack,
+            #[allow(unreachable_patterns)]
+            _ => return Err(unsupported()),
+        },
+    })
 }
 
 #[derive(Debug)]
@@ -124,3 +623,86 @@ pub enum BindExecuteState {
     Begin,
     Release,
 }
+
+/// A MIDI note starting or ending, which binds are executed for
+#[derive(Clone)]
+pub struct NoteEvent {
+    /// The note played
+    pub note: Note,
+    /// How hard the note was played
+    pub vel: u8,
+    /// MIDI channel, numbered from 0
+    pub channel: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a combo of the given key held with Ctrl, so a modifier would be pressed before the key if allowed
+    fn with_ctrl(key: Key) -> KeyCombo {
+        KeyCombo {
+            modifiers: vec![Modifier::Control],
+            key,
+        }
+    }
+
+    #[test]
+    fn orders_modifiers_before_key() {
+        let keys = enigo_keys(&KeyCombo {
+            modifiers: vec![Modifier::Shift, Modifier::Control],
+            key: Key::Char('t'),
+        })
+        .unwrap();
+
+        let keys: Vec<String> = keys.iter().map(|(x, _)| x.to_string()).collect();
+        assert_eq!(keys, ["Shift", "Ctrl", "t"]);
+    }
+
+    #[test]
+    fn rejects_function_keys_past_f24() {
+        assert!(enigo_keys(&with_ctrl(Key::Function(25))).is_err());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn presses_every_key_on_linux() {
+        for key in NamedKey::ALL {
+            assert!(enigo_key(&Key::Named(key)).is_ok(), "{}", key.name());
+        }
+        for number in 1..=24 {
+            assert!(enigo_keys(&with_ctrl(Key::Function(number))).is_ok());
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn rejects_scroll_lock_on_windows() {
+        assert!(enigo_keys(&with_ctrl(Key::Named(NamedKey::ScrollLock))).is_err());
+        assert!(enigo_keys(&with_ctrl(Key::Function(24))).is_ok());
+    }
+
+    #[test]
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    fn rejects_unsupported_keys_on_macos() {
+        for key in [
+            Key::Function(21),
+            Key::Function(24),
+            Key::Named(NamedKey::Insert),
+            Key::Named(NamedKey::MediaPlayPause),
+        ] {
+            assert!(enigo_keys(&with_ctrl(key)).is_err());
+        }
+        assert!(enigo_keys(&with_ctrl(Key::Function(20))).is_ok());
+    }
+
+    #[test]
+    fn presses_back_and_forward_only_on_windows_and_linux() {
+        let supported = cfg!(any(target_os = "windows", target_os = "linux"));
+
+        for button in [MouseButton::Back, MouseButton::Forward] {
+            assert_eq!(enigo_mouse_button(&button).is_ok(), supported);
+        }
+        assert!(enigo_mouse_button(&MouseButton::Left).is_ok());
+    }
+}
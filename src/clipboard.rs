@@ -0,0 +1,192 @@
+//! Access to the system clipboard, through the clipboard programs each platform has.
+//!
+//! On Linux and other Unix-like systems, this needs `wl-clipboard` to be installed under Wayland, or `xclip` or
+//! `xsel` under X11. macOS and Windows have what's needed built in.
+//!
+//! The programs are given a few seconds to finish, so one which stops responding can't leave a bind stuck.
+
+use std::{
+    io::{Read, Write},
+    process::{Command, Output, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+
+/// How long a clipboard program is given to finish before it's stopped
+const TIMEOUT: Duration = Duration::from_secs(3);
+/// How often a clipboard program is checked on to see if it's finished
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Programs which put text on the clipboard, given it on standard input, in the order they're tried
+#[cfg(all(unix, not(target_os = "macos")))]
+const SET_PROGRAMS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+];
+/// Programs which print the text on the clipboard, in the order they're tried
+#[cfg(all(unix, not(target_os = "macos")))]
+const GET_PROGRAMS: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline", "--type", "text"]),
+    ("xclip", &["-selection", "clipboard", "-out"]),
+    ("xsel", &["--clipboard", "--output"]),
+];
+
+/// Programs which put text on the clipboard, given it on standard input, in the order they're tried
+#[cfg(target_os = "macos")]
+const SET_PROGRAMS: &[(&str, &[&str])] = &[("pbcopy", &[])];
+/// Programs which print the text on the clipboard, in the order they're tried
+#[cfg(target_os = "macos")]
+const GET_PROGRAMS: &[(&str, &[&str])] = &[("pbpaste", &[])];
+
+/// Programs which put text on the clipboard, given it on standard input, in the order they're tried
+#[cfg(windows)]
+const SET_PROGRAMS: &[(&str, &[&str])] = &[(
+    "powershell",
+    &[
+        "-NoProfile",
+        "-Command",
+        "[Console]::InputEncoding = [Text.Encoding]::UTF8; Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+    ],
+)];
+/// Programs which print the text on the clipboard, in the order they're tried
+#[cfg(windows)]
+const GET_PROGRAMS: &[(&str, &[&str])] = &[(
+    "powershell",
+    &[
+        "-NoProfile",
+        "-Command",
+        "[Console]::OutputEncoding = [Text.Encoding]::UTF8; Get-Clipboard -Raw",
+    ],
+)];
+
+/// Put the given text on the clipboard
+pub fn set(text: &str) -> anyhow::Result<()> {
+    for (program, args) in SET_PROGRAMS {
+        // Try the next program if this one isn't installed or can't be used here
+        let Some(output) = run(program, args, Some(text))? else {
+            continue;
+        };
+
+        if !output.status.success() {
+            bail!("`{program}` couldn't put the text on the clipboard");
+        }
+
+        return Ok(());
+    }
+
+    bail!("{}", missing_programs_message())
+}
+
+/// Returns the text on the clipboard, or None if there's no text on it, such as when it's empty or holds an image
+pub fn get() -> anyhow::Result<Option<String>> {
+    for (program, args) in GET_PROGRAMS {
+        // Try the next program if this one isn't installed or can't be used here
+        let Some(output) = run(program, args, None)? else {
+            continue;
+        };
+
+        // Most programs fail when there's no text to give
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let Ok(text) = String::from_utf8(output.stdout) else {
+            return Ok(None);
+        };
+
+        // PowerShell ends what it prints with a new line of its own
+        #[cfg(windows)]
+        let text = text
+            .strip_suffix("\r\n")
+            .map_or(text.clone(), str::to_owned);
+
+        // Others print nothing, which is no use to put back either
+        if text.is_empty() {
+            return Ok(None);
+        }
+
+        return Ok(Some(text));
+    }
+
+    bail!("{}", missing_programs_message())
+}
+
+/// Run a clipboard program, giving it the input if there is any, and returning how it finished and what it printed.
+///
+/// Returns None if the program isn't installed, or is for another display server than the one in use
+fn run(program: &str, args: &[&str], input: Option<&str>) -> anyhow::Result<Option<Output>> {
+    // wl-clipboard only works under Wayland, and fails rather than leaving it to the X11 programs otherwise
+    if program.starts_with("wl-") && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        return Ok(None);
+    }
+
+    let Ok(mut child) = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(if input.is_some() {
+            Stdio::null()
+        } else {
+            Stdio::piped()
+        })
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return Ok(None);
+    };
+
+    // Written and read on their own threads, so a program which stops responding can't hold things up past the timeout
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        let input = input.to_owned();
+        thread::spawn(move || stdin.write_all(input.as_bytes()));
+    }
+    let reader = child.stdout.take().map(|mut stdout| {
+        thread::spawn(move || {
+            let mut printed = Vec::new();
+            stdout.read_to_end(&mut printed).map(|_| printed)
+        })
+    });
+
+    let deadline = Instant::now() + TIMEOUT;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("`{program}` didn't finish in time");
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let stdout = match reader.map(thread::JoinHandle::join) {
+        Some(Ok(printed)) => printed?,
+        Some(Err(_)) => bail!("couldn't read what `{program}` printed"),
+        None => Vec::new(),
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout,
+        stderr: Vec::new(),
+    }))
+}
+
+/// Explains which programs are needed to use the clipboard
+fn missing_programs_message() -> String {
+    let programs: Vec<&str> = SET_PROGRAMS.iter().map(|(x, _)| *x).collect();
+
+    format!(
+        "couldn't use the clipboard, as none of {} could be run",
+        programs.join(", ")
+    )
+}
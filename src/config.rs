@@ -1,18 +1,50 @@
+use std::{collections::BTreeMap, time::Duration};
+
 use anyhow::{anyhow, bail};
 use serde::{Deserialize, Serialize};
 
-use crate::note::Note;
+use crate::{key::KeyCombo, note::Note};
 
 /// Persistent app data, intended to be stored in a file
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     binds: Vec<Bind>,
+    /// Timings used to recognise gestures on notes
+    #[serde(default)]
+    gestures: GestureConfig,
+    /// Layers of binds which can be switched to while playing, from lowest to highest priority
+    #[serde(default)]
+    layers: Vec<Layer>,
+    /// Changes made to incoming MIDI before it's matched against binds
+    #[serde(default)]
+    transform: InputTransform,
+    /// Limits on how often binds can act
+    #[serde(default)]
+    limits: LimitConfig,
+    /// Note which releases everything being held and stops everything in progress when played, if any
+    #[serde(default)]
+    panic_note: Option<Note>,
+    /// Lighting up pads on the controller to show what their binds are doing
+    #[serde(default)]
+    feedback: FeedbackConfig,
+    /// Routes forwarding incoming MIDI on to other outputs, such as a DAW
+    #[serde(default)]
+    thru: Vec<ThruRoute>,
 }
 
 impl Config {
     /// Create a new, empty data store
     pub fn new() -> Self {
-        Config { binds: vec![] }
+        Config {
+            binds: vec![],
+            gestures: GestureConfig::default(),
+            layers: vec![],
+            transform: InputTransform::default(),
+            limits: LimitConfig::default(),
+            panic_note: None,
+            feedback: FeedbackConfig::default(),
+            thru: vec![],
+        }
     }
 
     /// Returns a clone of the bind at the given index, if it exists
@@ -47,18 +79,31 @@ impl Config {
         Ok(())
     }
 
-    /// Returns clones of all binds for the given note
-    pub fn get_binds_for_note(&self, note: &Note) -> Vec<Bind> {
-        self.binds
+    /// Returns clones of all binds for the given note along with their indexes, given the names of the active layers from lowest to highest priority.
+    ///
+    /// Only the binds from the highest priority layer with any binds for the note are returned,
+    /// falling back to the base layer if none of the active layers have any.
+    pub fn get_binds_for_note(&self, note: &Note, active_layers: &[String]) -> Vec<(usize, Bind)> {
+        let binds_in_layer = |layer: Option<&String>| -> Vec<(usize, Bind)> {
+            self.binds
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, x)| {
+                    if x.note == *note && x.layer.as_ref() == layer {
+                        Some((idx, x.clone()))
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        };
+
+        active_layers
             .iter()
-            .filter_map(|x| {
-                if x.note == *note {
-                    Some(x.clone())
-                } else {
-                    None
-                }
-            })
-            .collect()
+            .rev()
+            .map(|x| binds_in_layer(Some(x)))
+            .find(|x| !x.is_empty())
+            .unwrap_or_else(|| binds_in_layer(None))
     }
 
     /// Returns the note for a bind as a human-readable string, if it exists
@@ -71,6 +116,19 @@ impl Config {
         Ok(self.get_bind(idx)?.action.name())
     }
 
+    /// Returns the name of the layer a bind is in, if it exists
+    pub fn get_bind_layer_string(&self, idx: usize) -> anyhow::Result<String> {
+        Ok(self
+            .get_bind(idx)?
+            .layer
+            .unwrap_or_else(|| BASE_LAYER_NAME.into()))
+    }
+
+    /// Returns a textual description of the trigger of a bind, if it exists
+    pub fn get_bind_trigger_string(&self, idx: usize) -> anyhow::Result<String> {
+        Ok(self.get_bind(idx)?.trigger.name())
+    }
+
     /// Returns the current number of binds
     pub fn len_binds(&self) -> usize {
         self.binds.len()
@@ -87,6 +145,380 @@ impl Config {
 
         Ok(())
     }
+
+    /// Returns a clone of the gesture timings
+    pub fn get_gesture_config(&self) -> GestureConfig {
+        self.gestures.clone()
+    }
+
+    /// Replace the gesture timings with the given ones
+    pub fn set_gesture_config(&mut self, gestures: GestureConfig) {
+        self.gestures = gestures;
+    }
+
+    /// Returns a clone of the input transform
+    pub fn get_input_transform(&self) -> InputTransform {
+        self.transform.clone()
+    }
+
+    /// Replace the input transform with the given one
+    pub fn set_input_transform(&mut self, transform: InputTransform) {
+        self.transform = transform;
+    }
+
+    /// Returns a clone of the limits on how often binds can act
+    pub fn get_limit_config(&self) -> LimitConfig {
+        self.limits.clone()
+    }
+
+    /// Replace the limits on how often binds can act with the given ones
+    pub fn set_limit_config(&mut self, limits: LimitConfig) {
+        self.limits = limits;
+    }
+
+    /// Returns a clone of the note which releases everything when played, if there is one
+    pub fn get_panic_note(&self) -> Option<Note> {
+        self.panic_note.clone()
+    }
+
+    /// Replace the note which releases everything when played with the given one
+    pub fn set_panic_note(&mut self, note: Option<Note>) {
+        self.panic_note = note;
+    }
+
+    /// Returns a clone of the config for lighting up pads on the controller
+    pub fn get_feedback_config(&self) -> FeedbackConfig {
+        self.feedback.clone()
+    }
+
+    /// Replace the config for lighting up pads on the controller with the given one
+    pub fn set_feedback_config(&mut self, feedback: FeedbackConfig) {
+        self.feedback = feedback;
+    }
+
+    /// Returns clones of all MIDI thru routes
+    pub fn get_thru_routes(&self) -> Vec<ThruRoute> {
+        self.thru.clone()
+    }
+
+    /// Create a new MIDI thru route with a default value, returning it's index
+    pub fn add_default_thru_route(&mut self) -> usize {
+        self.thru.push(ThruRoute::default());
+        self.thru.len() - 1
+    }
+
+    /// Set the given index, if in bounds, to the given MIDI thru route
+    pub fn set_thru_route(&mut self, idx: usize, route: ThruRoute) -> anyhow::Result<()> {
+        if route.lowest_note > route.highest_note {
+            bail!("the lowest note can't be higher than the highest note");
+        }
+
+        *self
+            .thru
+            .get_mut(idx)
+            .ok_or(anyhow!("index out of bounds for thru routes"))? = route;
+
+        Ok(())
+    }
+
+    /// Delete the MIDI thru route at the given index, if it exists
+    pub fn delete_thru_route(&mut self, idx: usize) -> anyhow::Result<()> {
+        // Check bounds
+        if idx >= self.thru.len() {
+            bail!("index out of bounds for thru routes");
+        }
+
+        self.thru.remove(idx);
+
+        Ok(())
+    }
+
+    /// Returns clones of all layers
+    pub fn get_layers(&self) -> Vec<Layer> {
+        self.layers.clone()
+    }
+
+    /// Returns a clone of the layer switched by the given note, if there is one
+    pub fn get_layer_switched_by(&self, note: &Note) -> Option<Layer> {
+        self.layers
+            .iter()
+            .find(|x| x.activation.note() == Some(note))
+            .cloned()
+    }
+
+    /// Create a new layer with a default value and an unused name, returning it's index
+    pub fn add_default_layer(&mut self) -> usize {
+        // Out of one more names than there are layers, at least one must be unused
+        let name = (1..=self.layers.len() + 1)
+            .map(|x| format!("Layer {x}"))
+            .find(|x| self.layers.iter().all(|layer| layer.name != *x))
+            .expect("there should always be an unused layer name");
+
+        self.layers.push(Layer {
+            name,
+            activation: LayerActivation::default(),
+        });
+        self.layers.len() - 1
+    }
+
+    /// Set the given index, if in bounds, to the given layer.
+    /// Binds in the layer are kept in it if it's renamed.
+    pub fn set_layer(&mut self, idx: usize, layer: Layer) -> anyhow::Result<()> {
+        if layer.name.is_empty() || layer.name == BASE_LAYER_NAME {
+            bail!("invalid layer name");
+        }
+
+        if self
+            .layers
+            .iter()
+            .enumerate()
+            .any(|(i, x)| i != idx && x.name == layer.name)
+        {
+            bail!("a layer with that name already exists");
+        }
+
+        let idx_layer = self
+            .layers
+            .get_mut(idx)
+            .ok_or(anyhow!("index out of bounds for layers"))?;
+
+        let new_name = layer.name.clone();
+        let old_name = std::mem::replace(idx_layer, layer).name;
+
+        for bind in &mut self.binds {
+            if bind.layer.as_ref() == Some(&old_name) {
+                bind.layer = Some(new_name.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delete the layer at the given index, if it exists. Binds in the layer are moved to the base layer.
+    pub fn delete_layer(&mut self, idx: usize) -> anyhow::Result<()> {
+        // Check bounds
+        if idx >= self.layers.len() {
+            bail!("index out of bounds for layers");
+        }
+
+        let layer = self.layers.remove(idx);
+
+        for bind in &mut self.binds {
+            if bind.layer.as_ref() == Some(&layer.name) {
+                bind.layer = None;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Changes made to incoming MIDI messages before they're matched against binds
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct InputTransform {
+    /// Semitones to shift every note by
+    pub transpose: i8,
+    /// Octaves to shift every note by
+    pub octave_shift: i8,
+    /// Extra semitones to shift notes from each MIDI input port by, keyed by port name
+    pub port_transpose: BTreeMap<String, i8>,
+    /// MIDI note numbers to replace with others, applied to notes as they arrive, before they're shifted
+    pub note_map: BTreeMap<u8, u8>,
+    /// MIDI channels to replace with others, numbered from 0
+    pub channel_map: BTreeMap<u8, u8>,
+    /// How the velocities of notes are changed
+    pub velocity_curve: VelocityCurve,
+}
+
+impl InputTransform {
+    /// Returns the MIDI note number a note from the given port becomes, or `None` if it's shifted out of range
+    pub fn note(&self, midi: u8, port: Option<&str>) -> Option<u8> {
+        let midi = self.note_map.get(&midi).copied().unwrap_or(midi);
+
+        let shift = i16::from(self.transpose)
+            + 12 * i16::from(self.octave_shift)
+            + port
+                .and_then(|x| self.port_transpose.get(x))
+                .map_or(0, |x| i16::from(*x));
+
+        u8::try_from(i16::from(midi) + shift)
+            .ok()
+            .filter(|x| *x <= 127)
+    }
+
+    /// Returns the channel a message on the given channel is moved to
+    pub fn channel(&self, channel: u8) -> u8 {
+        self.channel_map.get(&channel).copied().unwrap_or(channel)
+    }
+}
+
+/// A mapping from the velocity a note is played with to the velocity it's treated as having
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum VelocityCurve {
+    /// Velocities are unchanged
+    #[default]
+    Linear,
+    /// Every note has the same velocity
+    Fixed(u8),
+    /// Differences between soft notes are reduced, making everything louder
+    Compressed,
+    /// Differences between soft notes are exaggerated, making everything quieter
+    Expanded,
+    /// Velocities are interpolated between the given points, each mapping an input velocity to an output velocity
+    Custom(Vec<(u8, u8)>),
+}
+
+impl VelocityCurve {
+    /// Numerical representation of the enum
+    pub fn index(&self) -> u8 {
+        match self {
+            VelocityCurve::Linear => 0,
+            VelocityCurve::Fixed(_) => 1,
+            VelocityCurve::Compressed => 2,
+            VelocityCurve::Expanded => 3,
+            VelocityCurve::Custom(_) => 4,
+        }
+    }
+
+    /// Returns the velocity a note played with the given velocity is treated as having
+    pub fn apply(&self, vel: u8) -> u8 {
+        // A velocity of 0 has a special meaning, so isn't changed
+        if vel == 0 {
+            return 0;
+        }
+
+        let power_curve = |exponent: f64| {
+            // Always in 0.0..=127.0, so the cast can't truncate or lose the sign
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let curved = ((f64::from(vel) / 127.0).powf(exponent) * 127.0).round() as u8;
+
+            curved
+        };
+
+        let curved = match self {
+            VelocityCurve::Linear => vel,
+            VelocityCurve::Fixed(x) => *x,
+            VelocityCurve::Compressed => power_curve(0.5),
+            VelocityCurve::Expanded => power_curve(2.0),
+            VelocityCurve::Custom(points) => {
+                let below = points
+                    .iter()
+                    .filter(|(x, _)| *x <= vel)
+                    .max_by_key(|(x, _)| *x);
+                let above = points
+                    .iter()
+                    .filter(|(x, _)| *x >= vel)
+                    .min_by_key(|(x, _)| *x);
+
+                match (below, above) {
+                    (Some((x0, y0)), Some((x1, y1))) if x1 > x0 => {
+                        let (x0, y0, x1, y1) = (
+                            i32::from(*x0),
+                            i32::from(*y0),
+                            i32::from(*x1),
+                            i32::from(*y1),
+                        );
+
+                        let y = y0 + (y1 - y0) * (i32::from(vel) - x0) / (x1 - x0);
+
+                        u8::try_from(y).expect("interpolated between two velocities")
+                    }
+                    (Some((_, y)), _) | (None, Some((_, y))) => *y,
+                    (None, None) => vel,
+                }
+            }
+        };
+
+        // Keep notes from becoming silent
+        curved.clamp(1, 127)
+    }
+}
+
+/// Name shown for the layer binds are in when they aren't in any other
+pub const BASE_LAYER_NAME: &str = "Base";
+
+/// A set of binds which apply while the layer is active, in place of those in the layers below it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Layer {
+    /// Unique name, which binds refer to the layer by
+    pub name: String,
+    /// What makes the layer active
+    pub activation: LayerActivation,
+}
+
+/// What makes a Layer active
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum LayerActivation {
+    /// Active while the sustain pedal (CC64) is held down
+    #[default]
+    WhileSustain,
+    /// Active while the given note is held down
+    WhileNoteHeld(Note),
+    /// Switched on and off each time the given note is played
+    ToggledByNote(Note),
+}
+
+impl LayerActivation {
+    /// Numerical representation of the enum
+    pub fn index(&self) -> u8 {
+        match self {
+            LayerActivation::WhileSustain => 0,
+            LayerActivation::WhileNoteHeld(_) => 1,
+            LayerActivation::ToggledByNote(_) => 2,
+        }
+    }
+
+    /// The note used to activate the layer, if there is one
+    pub fn note(&self) -> Option<&Note> {
+        match self {
+            LayerActivation::WhileSustain => None,
+            LayerActivation::WhileNoteHeld(note) | LayerActivation::ToggledByNote(note) => {
+                Some(note)
+            }
+        }
+    }
+}
+
+/// Timings used to tell apart the gestures a note can be played with
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GestureConfig {
+    /// Longest gap, in milliseconds, between a tap ending and the next starting for them to count as a double tap
+    pub double_tap_ms: u32,
+    /// Shortest time, in milliseconds, a note must be held for to count as a long press
+    pub long_press_ms: u32,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            double_tap_ms: 300,
+            long_press_ms: 500,
+        }
+    }
+}
+
+/// Limits on how often binds can act, to guard against floods of MIDI messages. None are set by default
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct LimitConfig {
+    /// Most binds that can act within any one second, or 0 for no limit
+    pub max_actions_per_second: u32,
+    /// Longest time, in milliseconds, any bind can be held by its note before it's released, or 0 for no limit
+    #[serde(default)]
+    pub max_hold_ms: u32,
+}
+
+impl LimitConfig {
+    /// Returns the longest time the given bind can be held by its note before it's released, if there's a limit.
+    /// The shorter of the bind's own limit and the global one is used
+    pub fn max_hold(&self, bind: &Bind) -> Option<Duration> {
+        let max_hold_ms = match (bind.max_hold_ms, self.max_hold_ms) {
+            (0, 0) => return None,
+            (0, x) | (x, 0) => x,
+            (x, y) => x.min(y),
+        };
+
+        Some(Duration::from_millis(max_hold_ms.into()))
+    }
 }
 
 /// A mapping of MIDI-note to action
@@ -96,6 +528,95 @@ pub struct Bind {
     pub note: Note,
     /// What should be done when the note is played
     pub action: BindAction,
+    /// How the note must be played for the action to happen
+    #[serde(default)]
+    pub trigger: BindTrigger,
+    /// Name of the layer the bind is in, or `None` for the base layer
+    #[serde(default)]
+    pub layer: Option<String>,
+    /// Name of the exclusive group the bind is in, if any. Only one bind in a group can be holding something at a time
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Should a held action stay held after the note ends, until the sustain pedal is lifted?
+    #[serde(default)]
+    pub sustain: bool,
+    /// Should a held action be latched by one press of the note and released by the next, rather than only being held while the note is?
+    #[serde(default)]
+    pub toggle: bool,
+    /// Should the note's pad on the controller be lit up while the bind is held or latched?
+    #[serde(default)]
+    pub feedback: bool,
+    /// Shortest time, in milliseconds, between the note starting twice for the second to act, or 0 to always act
+    #[serde(default)]
+    pub debounce_ms: u32,
+    /// Shortest time, in milliseconds, after the bind acts before it can act again, or 0 for no limit
+    #[serde(default)]
+    pub cooldown_ms: u32,
+    /// Longest time, in milliseconds, the note can be held while acting on the bind before it's released, or 0 for no limit
+    #[serde(default)]
+    pub max_hold_ms: u32,
+    /// Should the action be repeated while the note is held, and how often?
+    #[serde(default)]
+    pub auto_repeat: Option<AutoRepeat>,
+    /// A separate action done all at once when the note ends, after the main action is released
+    #[serde(default)]
+    pub release_action: Option<BindAction>,
+}
+
+/// Timings for repeating a bind's action while its note is held
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AutoRepeat {
+    /// Milliseconds after the note starts before the first repeat
+    pub delay_ms: u32,
+    /// Milliseconds between each repeat after the first
+    pub interval_ms: u32,
+}
+
+impl Default for AutoRepeat {
+    fn default() -> Self {
+        Self {
+            delay_ms: 500,
+            interval_ms: 100,
+        }
+    }
+}
+
+/// The way a note has to be played for a Bind to execute
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BindTrigger {
+    /// Executes as soon as the note starts, and releases when it ends
+    #[default]
+    Press,
+    /// Executes once after the note is briefly played
+    Tap,
+    /// Executes once after the note is briefly played twice in quick succession
+    DoubleTap,
+    /// Executes once the note is released, if it was held for at least the long press time. Nothing happens while
+    /// it's still held, however long that is
+    LongPress,
+}
+
+impl BindTrigger {
+    /// Numerical representation of the enum
+    pub fn index(self) -> u8 {
+        match self {
+            BindTrigger::Press => 0,
+            BindTrigger::Tap => 1,
+            BindTrigger::DoubleTap => 2,
+            BindTrigger::LongPress => 3,
+        }
+    }
+
+    /// String representation of the enum
+    pub fn name(self) -> String {
+        match self {
+            BindTrigger::Press => "Press",
+            BindTrigger::Tap => "Tap",
+            BindTrigger::DoubleTap => "Double Tap",
+            BindTrigger::LongPress => "Long Press",
+        }
+        .into()
+    }
 }
 
 /// Action taken when a Bind is executed
@@ -109,6 +630,20 @@ pub enum BindAction {
     MoveMouseAbsolute(AbsolutePos2D),
     Scroll(ScrollBindAction),
     Text(TextBindAction),
+    Macro(MacroBindAction),
+    MoveMouseContinuous(ContinuousMoveBindAction),
+    ScrollContinuous(ContinuousScrollBindAction),
+    Command(CommandBindAction),
+    SendMidi(SendMidiBindAction),
+    SendOsc(SendOscBindAction),
+    Http(HttpBindAction),
+    Clipboard(ClipboardBindAction),
+    Drag(DragBindAction),
+    ClickAt(ClickAtBindAction),
+    GlideMouseRelative(GlideRelativeBindAction),
+    GlideMouseAbsolute(GlideAbsoluteBindAction),
+    Cycle(CycleBindAction),
+    Random(RandomBindAction),
     Debug,
 }
 
@@ -130,7 +665,21 @@ impl BindAction {
             BindAction::MoveMouseAbsolute(_) => 5,
             BindAction::Scroll(_) => 6,
             BindAction::Text(_) => 7,
-            BindAction::Debug => 8,
+            BindAction::Macro(_) => 8,
+            BindAction::MoveMouseContinuous(_) => 9,
+            BindAction::ScrollContinuous(_) => 10,
+            BindAction::Command(_) => 11,
+            BindAction::SendMidi(_) => 12,
+            BindAction::SendOsc(_) => 13,
+            BindAction::Http(_) => 14,
+            BindAction::Clipboard(_) => 15,
+            BindAction::Drag(_) => 16,
+            BindAction::ClickAt(_) => 17,
+            BindAction::GlideMouseRelative(_) => 18,
+            BindAction::GlideMouseAbsolute(_) => 19,
+            BindAction::Cycle(_) => 20,
+            BindAction::Random(_) => 21,
+            BindAction::Debug => 22,
         }
     }
 
@@ -145,25 +694,63 @@ impl BindAction {
             BindAction::MoveMouseAbsolute(_) => "Move Mouse to",
             BindAction::Scroll(_) => "Scroll",
             BindAction::Text(_) => "Text",
+            BindAction::Macro(_) => "Macro",
+            BindAction::MoveMouseContinuous(_) => "Move Mouse Continuously",
+            BindAction::ScrollContinuous(_) => "Scroll Continuously",
+            BindAction::Command(_) => "Run Command",
+            BindAction::SendMidi(_) => "Send MIDI",
+            BindAction::SendOsc(_) => "Send OSC",
+            BindAction::Http(_) => "HTTP Request",
+            BindAction::Clipboard(_) => "Clipboard",
+            BindAction::Drag(_) => "Drag",
+            BindAction::ClickAt(_) => "Click at",
+            BindAction::GlideMouseRelative(_) => "Glide Mouse",
+            BindAction::GlideMouseAbsolute(_) => "Glide Mouse to",
+            BindAction::Cycle(_) => "Cycle",
+            BindAction::Random(_) => "Random",
             BindAction::Debug => "Debug",
         }
         .into()
     }
+
+    /// Does the action hold something down from when it begins until it's released?
+    pub fn is_hold(&self) -> bool {
+        matches!(self, BindAction::HoldKey(_) | BindAction::HoldClick(_))
+    }
+
+    /// Can the action be repeated while its note is held? Held and continuous actions already last as long as the note,
+    /// repeatedly starting a program would soon get out of hand, and drags and glides would pile up on each other
+    pub fn can_repeat(&self) -> bool {
+        !self.is_hold()
+            && !matches!(
+                self,
+                BindAction::MoveMouseContinuous(_)
+                    | BindAction::ScrollContinuous(_)
+                    | BindAction::Command(_)
+                    | BindAction::Drag(_)
+                    | BindAction::GlideMouseRelative(_)
+                    | BindAction::GlideMouseAbsolute(_)
+            )
+    }
 }
 
 /// Data for an Action simulating a keypress
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct KeyboardKeyBindAction {
-    pub key: String,
+    pub key: KeyCombo,
 }
 
 /// Data for an Action simulating a mouse click
-#[derive(Clone, Default, Serialize, Deserialize)]
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MouseButton {
     #[default]
     Left,
     Right,
     Middle,
+    /// The side button which usually goes back, such as in a web browser
+    Back,
+    /// The side button which usually goes forward, such as in a web browser
+    Forward,
 }
 
 impl MouseButton {
@@ -173,6 +760,8 @@ impl MouseButton {
             MouseButton::Left => 0,
             MouseButton::Right => 1,
             MouseButton::Middle => 2,
+            MouseButton::Back => 3,
+            MouseButton::Forward => 4,
         }
     }
 }
@@ -186,15 +775,148 @@ pub struct RelativePos2D {
 /// Data for an Action changing a 2D position to an absolute value
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct AbsolutePos2D {
+    /// Pixels from the left of the screen, unless the position is on a monitor
     pub x: i32,
+    /// Pixels from the top of the screen, unless the position is on a monitor
     pub y: i32,
+    /// Where on a monitor the position is, as a percentage of its size, which is used instead of the pixels
+    #[serde(default)]
+    pub monitor: Option<MonitorPos2D>,
+}
+
+/// A position as percentages of a monitor's size, so it stays in the same place when the resolution or the layout
+/// of the monitors change
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct MonitorPos2D {
+    pub monitor: MonitorChoice,
+    /// Percentage of the way across the monitor from its left edge
+    pub x_percent: f64,
+    /// Percentage of the way down the monitor from its top edge
+    pub y_percent: f64,
+}
+
+/// Which monitor a position is on
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum MonitorChoice {
+    /// Whichever monitor the cursor is on at the time
+    #[default]
+    Cursor,
+    /// The monitor the system treats as the main one
+    Primary,
+    /// A monitor by its number, counting from 1 in the order the system lists them
+    Numbered(usize),
+    /// A monitor by the name the system gives it, such as `DP-1` or `\\.\DISPLAY1`
+    Named(String),
+}
+
+impl MonitorChoice {
+    /// Numerical representation of the enum
+    pub fn index(&self) -> u8 {
+        match self {
+            MonitorChoice::Cursor => 0,
+            MonitorChoice::Primary => 1,
+            MonitorChoice::Numbered(_) => 2,
+            MonitorChoice::Named(_) => 3,
+        }
+    }
+}
+
+/// Data for an Action dragging the mouse from one position to another with a button held down
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct DragBindAction {
+    pub button: MouseButton,
+    pub from: AbsolutePos2D,
+    pub to: AbsolutePos2D,
+    pub glide: Glide,
+}
+
+/// Data for an Action clicking at a position on the screen
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClickAtBindAction {
+    pub button: MouseButton,
+    pub position: AbsolutePos2D,
+    /// Move the cursor back to where it was before the click
+    pub return_cursor: bool,
+}
+
+impl Default for ClickAtBindAction {
+    fn default() -> Self {
+        Self {
+            button: MouseButton::default(),
+            position: AbsolutePos2D::default(),
+            return_cursor: true,
+        }
+    }
+}
+
+/// Data for an Action smoothly moving the mouse by a relative offset
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct GlideRelativeBindAction {
+    pub offset: RelativePos2D,
+    pub glide: Glide,
+}
+
+/// Data for an Action smoothly moving the mouse to an absolute position
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct GlideAbsoluteBindAction {
+    pub position: AbsolutePos2D,
+    pub glide: Glide,
+}
+
+/// How the mouse moves over time between two positions, rather than jumping straight there
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Glide {
+    /// How long the movement takes, where 0 jumps straight there
+    pub duration_ms: u32,
+    pub easing: Easing,
+}
+
+impl Default for Glide {
+    fn default() -> Self {
+        Self {
+            duration_ms: 250,
+            easing: Easing::default(),
+        }
+    }
+}
+
+/// How the speed of a glide changes as it goes
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Easing {
+    /// The same speed the whole way
+    Linear,
+    /// Starting slowly and speeding up
+    EaseIn,
+    /// Starting quickly and slowing down
+    EaseOut,
+    /// Starting and finishing slowly
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Numerical representation of the enum
+    pub fn index(self) -> u8 {
+        match self {
+            Easing::Linear => 0,
+            Easing::EaseIn => 1,
+            Easing::EaseOut => 2,
+            Easing::EaseInOut => 3,
+        }
+    }
 }
 
 /// Data for an Action simulating mouse scroll
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct ScrollBindAction {
     pub direction: ScrollDirection,
+    /// Lines to scroll, or hundredths of a line if precise
     pub amount: i32,
+    /// Scroll by hundredths of a line, carrying over what doesn't add up to a whole line to the next scroll
+    #[serde(default)]
+    pub precise: bool,
 }
 
 /// Cardinal screen direction
@@ -219,8 +941,421 @@ impl ScrollDirection {
     }
 }
 
-/// Data for an Action typing a string of text
+/// Data for an Action moving the mouse repeatedly while the note is held
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ContinuousMoveBindAction {
+    /// How far to move the mouse each time, at the starting speed
+    pub step: RelativePos2D,
+    pub motion: ContinuousMotion,
+}
+
+/// Data for an Action scrolling repeatedly while the note is held
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ContinuousScrollBindAction {
+    /// How far to scroll each time, at the starting speed
+    pub step: ScrollBindAction,
+    pub motion: ContinuousMotion,
+}
+
+/// How often, and how quickly, a continuous action moves while the note is held
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ContinuousMotion {
+    /// Milliseconds between each movement
+    pub interval_ms: u32,
+    /// How much the speed increases by each second the note is held, as a percentage of the starting speed
+    pub acceleration: u32,
+    /// Should notes played harder move faster?
+    pub velocity_sensitive: bool,
+}
+
+impl Default for ContinuousMotion {
+    fn default() -> Self {
+        Self {
+            interval_ms: 20,
+            acceleration: 0,
+            velocity_sensitive: false,
+        }
+    }
+}
+
+/// Data for an Action typing a string of text, which can press keys partway through, like `Hello{Enter}`
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct TextBindAction {
     pub text: String,
+    /// Milliseconds to wait between typing each character or key, or 0 to type it all at once
+    #[serde(default)]
+    pub char_delay_ms: u32,
+}
+
+/// Data for an Action performing a sequence of other actions
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct MacroBindAction {
+    /// What to do, in order
+    pub steps: Vec<MacroStep>,
+    /// How many more times to go through the steps after the first
+    #[serde(default)]
+    pub repeat: u32,
+    /// Should the macro stop partway through if the note ends?
+    #[serde(default)]
+    pub cancel_on_release: bool,
+}
+
+/// A single step of a macro
+#[derive(Clone, Serialize, Deserialize)]
+pub enum MacroStep {
+    /// Perform an action. Held actions stay held until the macro ends
+    Action(BindAction),
+    /// Wait for the given number of milliseconds before the next step
+    Wait(u32),
+}
+
+/// Data for an Action which acts as each of its actions in turn, moving on to the next every time it's triggered
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CycleBindAction {
+    /// What to act as, in order
+    pub actions: Vec<BindAction>,
+    /// Go back to the first action after the last, rather than staying on the last
+    pub wrap: bool,
+    /// Index of the action to act as next, kept up to date in the config while `persist` is set
+    pub position: usize,
+    /// Carry on from the same action after bindmidi is restarted, rather than starting from the first
+    pub persist: bool,
+    /// Go back to the first action if not triggered for this many milliseconds, or 0 to never
+    pub reset_after_ms: u32,
+    /// Go back to the first action whenever the MIDI connection is started
+    pub reset_on_connect: bool,
+}
+
+impl Default for CycleBindAction {
+    fn default() -> Self {
+        Self {
+            actions: vec![],
+            wrap: true,
+            position: 0,
+            persist: false,
+            reset_after_ms: 0,
+            reset_on_connect: false,
+        }
+    }
+}
+
+/// Data for an Action which acts as one of its actions, picked at random every time it's triggered
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct RandomBindAction {
+    pub choices: Vec<WeightedAction>,
+    /// Never pick the same action twice in a row, if there's anything else to pick
+    #[serde(default)]
+    pub no_repeat: bool,
+}
+
+/// An action which can be picked at random, with how likely it is to be picked compared to the others
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WeightedAction {
+    pub weight: u32,
+    pub action: BindAction,
+}
+
+/// Data for an Action running a program
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CommandBindAction {
+    /// Name or path of the program to run
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Directory to run the program in, or `None` for bindmidi's own
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    /// Environment variables set for the program, on top of bindmidi's own
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// What happens to the program when the note ends
+    #[serde(default)]
+    pub on_release: CommandRelease,
+}
+
+impl CommandBindAction {
+    /// Does this run exactly the same program in the same way as the other, whatever happens when the note ends?
+    pub fn runs_same_as(&self, other: &Self) -> bool {
+        self.program == other.program
+            && self.args == other.args
+            && self.working_dir == other.working_dir
+            && self.env == other.env
+    }
+}
+
+/// Data for an Action sending a MIDI message out, such as to another program or a synth
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SendMidiBindAction {
+    /// Name of the output port to send to, or `None` for bindmidi's own virtual port
+    #[serde(default)]
+    pub port: Option<String>,
+    pub message: MidiOutMessage,
+}
+
+/// Data for an Action sending an OSC message over UDP, such as to lighting or video software
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SendOscBindAction {
+    /// Host name or IP address to send to
+    pub host: String,
+    /// UDP port to send to
+    pub port: u16,
+    /// Address of the message, like `/cue/1/go`
+    pub address: String,
+    #[serde(default)]
+    pub args: Vec<OscArgument>,
+}
+
+impl Default for SendOscBindAction {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".into(),
+            port: 53000,
+            address: "/go".into(),
+            args: vec![],
+        }
+    }
+}
+
+/// An argument of an OSC message. Numbers and text can contain placeholders, such as `{velocity}`,
+/// which are filled in from the note sending the message
+#[derive(Clone, Serialize, Deserialize)]
+pub enum OscArgument {
+    Int(String),
+    Float(String),
+    String(String),
+    Bool(bool),
+}
+
+/// Data for an Action putting text on the clipboard, and optionally pasting it. This is more reliable than typing
+/// long text or characters the keyboard layout can't type. The text can contain placeholders, such as `{velocity}`,
+/// which are filled in from the note
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardBindAction {
+    pub text: String,
+    /// Whether the text is pasted once it's on the clipboard
+    pub paste: bool,
+    /// Whether what was on the clipboard before is put back after pasting
+    pub restore: bool,
+}
+
+/// Data for an Action sending an HTTP request, such as a webhook. The URL and body can contain placeholders,
+/// such as `{velocity}`, which are filled in from the note sending the request
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpBindAction {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    /// Sent with POST and PUT requests
+    pub body: String,
+    /// Longest time, in milliseconds, the whole request can take, from connecting to the end of the response
+    pub timeout_ms: u64,
+}
+
+impl Default for HttpBindAction {
+    fn default() -> Self {
+        Self {
+            method: HttpMethod::default(),
+            url: "http://localhost/".into(),
+            headers: BTreeMap::new(),
+            body: String::new(),
+            timeout_ms: 5000,
+        }
+    }
+}
+
+/// Kind of HTTP request
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub enum HttpMethod {
+    #[default]
+    Get,
+    Post,
+    Put,
+}
+
+impl HttpMethod {
+    /// Numerical representation of the enum
+    pub fn index(self) -> u8 {
+        match self {
+            HttpMethod::Get => 0,
+            HttpMethod::Post => 1,
+            HttpMethod::Put => 2,
+        }
+    }
+
+    /// Name of the method, as sent in the request
+    pub fn name(self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+        }
+    }
+
+    /// Is a body sent with requests using this method?
+    pub fn has_body(self) -> bool {
+        !matches!(self, HttpMethod::Get)
+    }
+}
+
+/// A MIDI message which can be sent by a bind. Channels count from 0
+#[derive(Clone, Serialize, Deserialize)]
+pub enum MidiOutMessage {
+    /// A note which starts when the action begins and ends when it's released
+    Note {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    ProgramChange {
+        channel: u8,
+        program: u8,
+    },
+    /// Any other message, written byte by byte
+    Raw(Vec<u8>),
+}
+
+impl Default for MidiOutMessage {
+    fn default() -> Self {
+        Self::Note {
+            channel: 0,
+            note: 60,
+            velocity: 127,
+        }
+    }
+}
+
+impl MidiOutMessage {
+    /// Numerical representation of the enum
+    pub fn index(&self) -> u8 {
+        match self {
+            MidiOutMessage::Note { .. } => 0,
+            MidiOutMessage::ControlChange { .. } => 1,
+            MidiOutMessage::ProgramChange { .. } => 2,
+            MidiOutMessage::Raw(_) => 3,
+        }
+    }
+}
+
+/// Lighting up pads on the controller to show what their binds are doing
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FeedbackConfig {
+    /// Name of the output port of the controller, or `None` to not light anything up
+    pub port: Option<String>,
+    /// MIDI channel to light pads up on, counting from 0
+    pub channel: u8,
+    /// Velocity sent to light a pad up, which chooses its colour on most controllers
+    pub lit_velocity: u8,
+    /// Velocity sent to turn a pad off
+    pub unlit_velocity: u8,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            port: None,
+            channel: 0,
+            lit_velocity: 127,
+            unlit_velocity: 0,
+        }
+    }
+}
+
+/// Forwarding of incoming MIDI on to an output, letting only some of it through and changing it on the way
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThruRoute {
+    /// Name of the input port to forward from, or `None` for any input
+    pub input: Option<String>,
+    /// Name of the output port to forward to, or `None` for bindmidi's own virtual port
+    pub output: Option<String>,
+    /// Channels to forward, counting from 0, or every channel if empty
+    pub channels: Vec<u8>,
+    /// Kinds of message to forward
+    pub messages: ThruMessages,
+    /// Lowest note forwarded, as a MIDI note number
+    pub lowest_note: u8,
+    /// Highest note forwarded, as a MIDI note number
+    pub highest_note: u8,
+    /// Semitones to shift forwarded notes by
+    pub transpose: i8,
+    /// Channel to send everything forwarded on, counting from 0, or `None` to keep the channel it came in on
+    pub output_channel: Option<u8>,
+    /// Whether notes which something is bound to are left out, so they only act on their binds
+    pub skip_bound_notes: bool,
+}
+
+impl Default for ThruRoute {
+    fn default() -> Self {
+        Self {
+            input: None,
+            output: None,
+            channels: vec![],
+            messages: ThruMessages::default(),
+            lowest_note: 0,
+            highest_note: 127,
+            transpose: 0,
+            output_channel: None,
+            skip_bound_notes: true,
+        }
+    }
+}
+
+/// Kinds of MIDI message forwarded by a thru route
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ThruMessages {
+    pub notes: bool,
+    pub control_changes: bool,
+    pub program_changes: bool,
+    pub pitch_bend: bool,
+    /// Pressure on held notes, both per note and per channel
+    pub aftertouch: bool,
+    /// Messages for every channel, such as clock, start, stop and system exclusive
+    pub system: bool,
+}
+
+impl Default for ThruMessages {
+    fn default() -> Self {
+        Self {
+            notes: true,
+            control_changes: true,
+            program_changes: true,
+            pitch_bend: true,
+            aftertouch: true,
+            system: true,
+        }
+    }
+}
+
+/// What happens to a program run by a bind when the note ends
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommandRelease {
+    /// Leave the program running on its own
+    #[default]
+    Detach,
+    /// Leave the program running, but don't run it again until it's exited. The bind's note doesn't wait for it, and
+    /// it's checked on about once a second to notice when it has
+    Wait,
+    /// Stop the program
+    Kill,
+}
+
+impl CommandRelease {
+    /// Numerical representation of the enum
+    pub fn index(self) -> u8 {
+        match self {
+            CommandRelease::Detach => 0,
+            CommandRelease::Wait => 1,
+            CommandRelease::Kill => 2,
+        }
+    }
 }
@@ -0,0 +1,104 @@
+//! Tracking of the keys and mouse buttons being held down, counted by the notes holding them.
+//!
+//! Without this, two notes both holding Shift would break each other's hold, as whichever ended first would release it.
+
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use crate::{config::MouseButton, key::Key, note::Note};
+
+/// Something which can be held down
+#[derive(Clone, PartialEq, Eq)]
+pub enum HeldInput {
+    Key(Key),
+    MouseButton(MouseButton),
+}
+
+impl Display for HeldInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeldInput::Key(x) => write!(f, "{x}"),
+            HeldInput::MouseButton(MouseButton::Left) => write!(f, "Left Mouse Button"),
+            HeldInput::MouseButton(MouseButton::Right) => write!(f, "Right Mouse Button"),
+            HeldInput::MouseButton(MouseButton::Middle) => write!(f, "Middle Mouse Button"),
+            HeldInput::MouseButton(MouseButton::Back) => write!(f, "Back Mouse Button"),
+            HeldInput::MouseButton(MouseButton::Forward) => write!(f, "Forward Mouse Button"),
+        }
+    }
+}
+
+/// An input being held down, and the notes holding it
+#[derive(Clone)]
+pub struct Hold {
+    pub input: HeldInput,
+    /// Notes holding the input, once for each time they've pressed it
+    pub notes: Vec<Note>,
+}
+
+/// Keeps count of which notes are holding each input down.
+///
+/// Clones share the same holds, as every executor presses the same keyboard and mouse.
+#[derive(Clone, Default)]
+pub struct HeldInputs {
+    holds: Arc<Mutex<Vec<Hold>>>,
+}
+
+impl HeldInputs {
+    /// Record a note pressing an input, returning true if it wasn't already held, so needs pressing
+    pub fn press(&self, input: HeldInput, note: &Note) -> bool {
+        let mut holds = self.holds.lock().unwrap();
+
+        if let Some(hold) = holds.iter_mut().find(|x| x.input == input) {
+            hold.notes.push(note.clone());
+            return false;
+        }
+
+        holds.push(Hold {
+            input,
+            notes: vec![note.clone()],
+        });
+
+        true
+    }
+
+    /// Record a note releasing an input, returning true if nothing is holding it any more, so needs releasing
+    pub fn release(&self, input: &HeldInput, note: &Note) -> bool {
+        let mut holds = self.holds.lock().unwrap();
+
+        let Some(idx) = holds.iter().position(|x| x.input == *input) else {
+            // Nothing is known to be holding it, so releasing it can't break anything
+            return true;
+        };
+
+        let hold = &mut holds[idx];
+
+        if let Some(note_idx) = hold.notes.iter().position(|x| x == note) {
+            hold.notes.remove(note_idx);
+        }
+
+        if hold.notes.is_empty() {
+            holds.remove(idx);
+            return true;
+        }
+
+        false
+    }
+
+    /// Returns every input currently held down, in the order they were first pressed
+    pub fn holds(&self) -> Vec<Hold> {
+        self.holds.lock().unwrap().clone()
+    }
+
+    /// Forget every hold, returning the inputs which were held in the order they were first pressed, so they can be released
+    pub fn take_all(&self) -> Vec<HeldInput> {
+        // Used while panicking too, when it matters more to release everything than to worry about why the lock was poisoned
+        let mut holds = self.holds.lock().unwrap_or_else(PoisonError::into_inner);
+
+        std::mem::take(&mut *holds)
+            .into_iter()
+            .map(|x| x.input)
+            .collect()
+    }
+}
@@ -0,0 +1,239 @@
+//! Sending of HTTP requests, such as webhooks to home automation, web APIs or CI jobs.
+//!
+//! Requests are made on their own thread with a timeout, so a slow or missing server can't hold anything else up.
+//! Only a few can be in flight at once, so a note played over and over can't start a thread for every request.
+//! Both `http://` and `https://` URLs are supported, with certificates checked against the usual web root certificates.
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+use anyhow::bail;
+
+use crate::{bind::NoteEvent, config::HttpBindAction, template::fill_template};
+
+/// Most requests which can be waiting on servers at once
+const MAX_IN_FLIGHT: usize = 8;
+
+/// Number of requests currently waiting on servers
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a request as in flight until it's dropped, however its thread ends
+struct InFlight;
+
+impl InFlight {
+    /// Count another request as in flight, unless too many already are
+    fn start() -> Option<Self> {
+        IN_FLIGHT
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |x| {
+                (x < MAX_IN_FLIGHT).then_some(x + 1)
+            })
+            .ok()
+            .map(|_| Self)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Send the request of an HTTP action in the background, filling in its URL and body from the note which sent it.
+///
+/// Problems sending it are only logged, as they're found after the bind has finished acting. If too many requests are
+/// already waiting on servers, this one is dropped and logged instead
+pub fn send(action: &HttpBindAction, event: &NoteEvent) {
+    let action = action.clone();
+    let url = fill_template(&action.url, event);
+    let body = fill_template(&action.body, event);
+
+    let Some(in_flight) = InFlight::start() else {
+        eprintln!(
+            "Couldn't send {} request to `{url}`, as {MAX_IN_FLIGHT} requests are already waiting on servers",
+            action.method.name()
+        );
+        return;
+    };
+
+    thread::spawn(move || {
+        let _in_flight = in_flight;
+
+        if let Err(e) = request(&action, &url, &body) {
+            eprintln!(
+                "Couldn't send {} request to `{url}`: {e:#}",
+                action.method.name()
+            );
+        }
+    });
+}
+
+/// Check a URL can be requested, returning what's wrong with it if not
+pub fn check_url(url: &str) -> anyhow::Result<()> {
+    let parsed = ureq::get(url)
+        .request_url()
+        .map_err(|_| anyhow::anyhow!("`{url}` isn't a URL"))?;
+
+    if !matches!(parsed.scheme(), "http" | "https") {
+        bail!("URLs must start with `http://` or `https://`");
+    }
+
+    Ok(())
+}
+
+/// Make an HTTP request, returning an error if it fails or the server doesn't respond with success
+fn request(action: &HttpBindAction, url: &str, body: &str) -> anyhow::Result<()> {
+    check_url(url)?;
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_millis(action.timeout_ms.max(1)))
+        .build();
+
+    let request = action.headers.iter().fold(
+        agent.request(action.method.name(), url),
+        |request, (name, value)| request.set(name, value),
+    );
+
+    let response = if action.method.has_body() {
+        request.send_string(body)
+    } else {
+        request.call()
+    };
+
+    match response {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, response)) => {
+            bail!(
+                "the server responded with `{code} {}`",
+                response.status_text()
+            )
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::{check_url, request, InFlight, MAX_IN_FLIGHT};
+    use crate::config::{HttpBindAction, HttpMethod};
+
+    /// Serve a single request on a local port with the given status, returning the URL to request and a handle giving
+    /// back the request as it was received
+    fn serve_once(status: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook?x=1", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut received = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                received.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            received.push_str(&String::from_utf8(body).unwrap());
+
+            reader
+                .get_mut()
+                .write_all(format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n").as_bytes())
+                .unwrap();
+
+            received
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn parses_urls() {
+        assert!(check_url("http://localhost:8123/api/webhook/x").is_ok());
+        assert!(check_url("https://example.com/").is_ok());
+        assert!(check_url("ftp://example.com/").is_err());
+        assert!(check_url("example.com").is_err());
+    }
+
+    #[test]
+    fn sends_request_with_headers_and_body() {
+        let (url, server) = serve_once("200 OK");
+
+        let action = HttpBindAction {
+            method: HttpMethod::Post,
+            url: url.clone(),
+            headers: BTreeMap::from([("X-Token".to_owned(), "secret".to_owned())]),
+            body: "{\"note\":60}".into(),
+            timeout_ms: 5000,
+        };
+        request(&action, &url, &action.body).unwrap();
+
+        let received = server.join().unwrap();
+        assert!(received.starts_with("POST /hook?x=1 HTTP/1.1\r\n"));
+        assert!(received.contains("X-Token: secret\r\n"));
+        assert!(received.ends_with("\r\n\r\n{\"note\":60}"));
+    }
+
+    #[test]
+    fn fails_on_error_status() {
+        let (url, server) = serve_once("500 Internal Server Error");
+
+        let action = HttpBindAction {
+            url: url.clone(),
+            ..Default::default()
+        };
+        let error = request(&action, &url, "").unwrap_err();
+
+        server.join().unwrap();
+        assert!(error.to_string().contains("500"));
+    }
+
+    #[test]
+    fn gives_up_on_slow_servers() {
+        // Accepts the connection but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let action = HttpBindAction {
+            url: url.clone(),
+            timeout_ms: 200,
+            ..Default::default()
+        };
+        let started = Instant::now();
+        assert!(request(&action, &url, "").is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        drop(listener);
+    }
+
+    #[test]
+    fn limits_requests_in_flight() {
+        let in_flight: Vec<InFlight> = (0..MAX_IN_FLIGHT)
+            .map_while(|_| InFlight::start())
+            .collect();
+        assert_eq!(in_flight.len(), MAX_IN_FLIGHT);
+        assert!(InFlight::start().is_none());
+
+        drop(in_flight);
+        assert!(InFlight::start().is_some());
+    }
+}
@@ -0,0 +1,414 @@
+use std::fmt::Display;
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+/// A keyboard key to press along with any modifiers held while it's pressed, written like `Ctrl+Shift+T`
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct KeyCombo {
+    /// Modifier keys held down around the key, with no duplicates
+    pub modifiers: Vec<Modifier>,
+    /// The main key
+    pub key: Key,
+}
+
+impl Default for KeyCombo {
+    fn default() -> Self {
+        Self {
+            modifiers: vec![],
+            key: Key::Char('a'),
+        }
+    }
+}
+
+impl Display for KeyCombo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for modifier in Modifier::ALL {
+            if self.modifiers.contains(&modifier) {
+                write!(f, "{}+", modifier.name())?;
+            }
+        }
+
+        write!(f, "{}", self.key)
+    }
+}
+
+impl TryFrom<&str> for KeyCombo {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+
+        // `+` can be the key itself, as in `Ctrl++`
+        let (modifiers, key) = if value == "+" {
+            ("", "+")
+        } else if let Some(modifiers) = value.strip_suffix("++") {
+            (modifiers, "+")
+        } else {
+            value.rsplit_once('+').unwrap_or(("", value))
+        };
+
+        let mut parsed_modifiers = vec![];
+
+        for modifier in modifiers.split('+').filter(|x| !x.trim().is_empty()) {
+            let modifier = Modifier::try_from(modifier)?;
+
+            if !parsed_modifiers.contains(&modifier) {
+                parsed_modifiers.push(modifier);
+            }
+        }
+
+        Ok(Self {
+            modifiers: parsed_modifiers,
+            key: Key::try_from(key)?,
+        })
+    }
+}
+
+impl From<String> for KeyCombo {
+    fn from(value: String) -> Self {
+        // Older configs stored keys as free text, of which only the first character was used
+        Self::try_from(value.as_str()).unwrap_or_else(|_| Self {
+            modifiers: vec![],
+            key: value.chars().next().map_or(Self::default().key, Key::Char),
+        })
+    }
+}
+
+impl From<KeyCombo> for String {
+    fn from(value: KeyCombo) -> Self {
+        value.to_string()
+    }
+}
+
+/// A key held down around another to change what it does
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Control,
+    Shift,
+    Alt,
+    /// The Windows key, or Command on macOS
+    Meta,
+}
+
+impl Modifier {
+    /// Every modifier, in the order they're written in
+    pub const ALL: [Modifier; 4] = [
+        Modifier::Control,
+        Modifier::Shift,
+        Modifier::Alt,
+        Modifier::Meta,
+    ];
+
+    /// String representation of the enum
+    pub fn name(self) -> &'static str {
+        match self {
+            Modifier::Control => "Ctrl",
+            Modifier::Shift => "Shift",
+            Modifier::Alt => "Alt",
+            Modifier::Meta => "Meta",
+        }
+    }
+
+    /// The key this modifier is pressed with
+    pub fn key(self) -> NamedKey {
+        match self {
+            Modifier::Control => NamedKey::Control,
+            Modifier::Shift => NamedKey::Shift,
+            Modifier::Alt => NamedKey::Alt,
+            Modifier::Meta => NamedKey::Meta,
+        }
+    }
+}
+
+impl TryFrom<&str> for Modifier {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value.trim().to_lowercase().as_str() {
+            "ctrl" | "control" => Modifier::Control,
+            "shift" => Modifier::Shift,
+            "alt" | "option" => Modifier::Alt,
+            "meta" | "super" | "win" | "windows" | "cmd" | "command" => Modifier::Meta,
+            _ => bail!("`{}` isn't a modifier key", value.trim()),
+        })
+    }
+}
+
+/// A single keyboard key
+#[derive(Clone, PartialEq, Eq)]
+pub enum Key {
+    /// The key which types the given character in the current keyboard layout
+    Char(char),
+    /// A key which doesn't type a character
+    Named(NamedKey),
+    /// A function key, numbered from 1
+    Function(u8),
+    /// A platform-specific keycode
+    Raw(u16),
+}
+
+/// Highest numbered function key which can be pressed
+pub const MAX_FUNCTION_KEY: u8 = 24;
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Key::Char(x) => write!(f, "{x}"),
+            Key::Named(x) => write!(f, "{}", x.name()),
+            Key::Function(x) => write!(f, "F{x}"),
+            Key::Raw(x) => write!(f, "Raw({x})"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Key {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+
+        if value.is_empty() {
+            bail!("no key given");
+        }
+
+        let mut chars = value.chars();
+        if let (Some(x), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Char(x));
+        }
+
+        if let Some(code) = value.strip_prefix("Raw(").and_then(|x| x.strip_suffix(')')) {
+            return Ok(Key::Raw(
+                code.trim()
+                    .parse()
+                    .map_err(|_| anyhow!("`{code}` isn't a valid keycode"))?,
+            ));
+        }
+
+        if let Some(number) = value.strip_prefix(['F', 'f']) {
+            if let Ok(number) = number.parse::<u8>() {
+                if !(1..=MAX_FUNCTION_KEY).contains(&number) {
+                    bail!("there's no function key F{number}");
+                }
+
+                return Ok(Key::Function(number));
+            }
+        }
+
+        let lowercase = value.to_lowercase();
+        NamedKey::ALL
+            .into_iter()
+            .find(|x| x.aliases().contains(&lowercase))
+            .map(Key::Named)
+            .ok_or(anyhow!("`{value}` isn't a known key"))
+    }
+}
+
+/// A key which doesn't type a character
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NamedKey {
+    Return,
+    Escape,
+    Tab,
+    Space,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+    Control,
+    Shift,
+    Alt,
+    Meta,
+    CapsLock,
+    NumLock,
+    ScrollLock,
+    PrintScreen,
+    Pause,
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    MediaPlayPause,
+    MediaStop,
+    MediaNext,
+    MediaPrevious,
+}
+
+impl NamedKey {
+    /// Every named key, in the order they're offered in the GUI
+    pub const ALL: [NamedKey; 31] = [
+        NamedKey::Return,
+        NamedKey::Escape,
+        NamedKey::Tab,
+        NamedKey::Space,
+        NamedKey::Backspace,
+        NamedKey::Delete,
+        NamedKey::Insert,
+        NamedKey::Home,
+        NamedKey::End,
+        NamedKey::PageUp,
+        NamedKey::PageDown,
+        NamedKey::Up,
+        NamedKey::Down,
+        NamedKey::Left,
+        NamedKey::Right,
+        NamedKey::Control,
+        NamedKey::Shift,
+        NamedKey::Alt,
+        NamedKey::Meta,
+        NamedKey::CapsLock,
+        NamedKey::NumLock,
+        NamedKey::ScrollLock,
+        NamedKey::PrintScreen,
+        NamedKey::Pause,
+        NamedKey::VolumeUp,
+        NamedKey::VolumeDown,
+        NamedKey::VolumeMute,
+        NamedKey::MediaPlayPause,
+        NamedKey::MediaStop,
+        NamedKey::MediaNext,
+        NamedKey::MediaPrevious,
+    ];
+
+    /// String representation of the enum
+    pub fn name(self) -> &'static str {
+        self.aliases_with_name()[0]
+    }
+
+    /// Lowercase names the key can be written as
+    fn aliases(self) -> Vec<String> {
+        self.aliases_with_name()
+            .iter()
+            .map(|x| x.to_lowercase())
+            .collect()
+    }
+
+    /// The name of the key, followed by any other names it can be written as
+    fn aliases_with_name(self) -> &'static [&'static str] {
+        match self {
+            NamedKey::Return => &["Enter", "Return"],
+            NamedKey::Escape => &["Escape", "Esc"],
+            NamedKey::Tab => &["Tab"],
+            NamedKey::Space => &["Space"],
+            NamedKey::Backspace => &["Backspace"],
+            NamedKey::Delete => &["Delete", "Del"],
+            NamedKey::Insert => &["Insert", "Ins"],
+            NamedKey::Home => &["Home"],
+            NamedKey::End => &["End"],
+            NamedKey::PageUp => &["PageUp", "PgUp"],
+            NamedKey::PageDown => &["PageDown", "PgDn"],
+            NamedKey::Up => &["Up", "UpArrow"],
+            NamedKey::Down => &["Down", "DownArrow"],
+            NamedKey::Left => &["Left", "LeftArrow"],
+            NamedKey::Right => &["Right", "RightArrow"],
+            NamedKey::Control => &["Ctrl", "Control"],
+            NamedKey::Shift => &["Shift"],
+            NamedKey::Alt => &["Alt", "Option"],
+            NamedKey::Meta => &["Meta", "Super", "Win", "Windows", "Cmd", "Command"],
+            NamedKey::CapsLock => &["CapsLock"],
+            NamedKey::NumLock => &["NumLock"],
+            NamedKey::ScrollLock => &["ScrollLock"],
+            NamedKey::PrintScreen => &["PrintScreen", "Print", "PrtSc"],
+            NamedKey::Pause => &["Pause"],
+            NamedKey::VolumeUp => &["VolumeUp"],
+            NamedKey::VolumeDown => &["VolumeDown"],
+            NamedKey::VolumeMute => &["VolumeMute", "Mute"],
+            NamedKey::MediaPlayPause => &["PlayPause"],
+            NamedKey::MediaStop => &["MediaStop"],
+            NamedKey::MediaNext => &["NextTrack"],
+            NamedKey::MediaPrevious => &["PreviousTrack", "PrevTrack"],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Key, KeyCombo, Modifier, NamedKey, MAX_FUNCTION_KEY};
+
+    /// Parse a key combo, then write it back out
+    fn normalise(value: &str) -> String {
+        KeyCombo::try_from(value).unwrap().to_string()
+    }
+
+    #[test]
+    fn round_trips_every_key() {
+        let keys = NamedKey::ALL
+            .into_iter()
+            .map(Key::Named)
+            .chain((1..=MAX_FUNCTION_KEY).map(Key::Function))
+            .chain([Key::Char('a'), Key::Char('+'), Key::Char('é'), Key::Raw(65)]);
+
+        for key in keys {
+            let combo = KeyCombo {
+                modifiers: Modifier::ALL.to_vec(),
+                key,
+            };
+            let written = combo.to_string();
+
+            assert!(
+                KeyCombo::try_from(written.as_str()).unwrap() == combo,
+                "{written}"
+            );
+        }
+    }
+
+    #[test]
+    fn writes_modifiers_in_order() {
+        assert_eq!(normalise("Meta+Shift+Ctrl+t"), "Ctrl+Shift+Meta+t");
+        assert_eq!(normalise("alt + CONTROL + x"), "Ctrl+Alt+x");
+        assert_eq!(normalise("Cmd+Command+s"), "Meta+s");
+    }
+
+    #[test]
+    fn matches_names_in_any_case() {
+        assert_eq!(normalise("ctrl+ESC"), "Ctrl+Escape");
+        assert_eq!(normalise("pgdn"), "PageDown");
+        assert_eq!(normalise("f12"), "F12");
+        assert_eq!(normalise("Shift+T"), "Shift+T");
+    }
+
+    #[test]
+    fn parses_plus_as_key() {
+        assert_eq!(normalise("+"), "+");
+        assert_eq!(normalise("Ctrl++"), "Ctrl++");
+    }
+
+    #[test]
+    fn parses_raw_keycodes() {
+        assert!(KeyCombo::try_from("Ctrl+Raw( 65 )").unwrap().key == Key::Raw(65));
+        assert!(KeyCombo::try_from("Raw(65536)").is_err());
+        assert!(KeyCombo::try_from("Raw(a)").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(KeyCombo::try_from("F25").is_err());
+        assert!(KeyCombo::try_from("F0").is_err());
+        assert!(KeyCombo::try_from("Hyper+a").is_err());
+        assert!(KeyCombo::try_from("Nonsense").is_err());
+        assert!(KeyCombo::try_from("").is_err());
+    }
+
+    #[test]
+    fn reads_old_free_text_keys_as_first_character() {
+        assert_eq!(KeyCombo::from(String::from("hello")).to_string(), "h");
+        assert_eq!(KeyCombo::from(String::from("Ctrl+q")).to_string(), "Ctrl+q");
+        assert!(KeyCombo::from(String::new()) == KeyCombo::default());
+    }
+
+    #[test]
+    fn stores_combos_as_strings() {
+        let json = serde_json::to_string(&KeyCombo::try_from("Shift+F5").unwrap()).unwrap();
+        assert_eq!(json, "\"Shift+F5\"");
+
+        let combo: KeyCombo = serde_json::from_str("\"x\"").unwrap();
+        assert_eq!(combo.to_string(), "x");
+    }
+}
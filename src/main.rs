@@ -2,9 +2,9 @@
 #![allow(clippy::too_many_lines, clippy::module_name_repetitions)]
 #![cfg_attr(not(test), windows_subsystem = "windows")]
 
-use std::{cell::RefCell, fs::read_to_string, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, fs::read_to_string, ops::RangeInclusive, rc::Rc};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail, Context};
 use directories::ProjectDirs;
 use libui::{
     controls::{Table, TableModel, TableParameters},
@@ -12,18 +12,48 @@ use libui::{
 };
 
 use crate::{
+    allowlist::CommandAllowlist,
+    bind::{Executor, ScrollRemainder},
     config::{
-        AbsolutePos2D, Bind, BindAction, Config, KeyboardKeyBindAction, RelativePos2D,
-        ScrollBindAction, TextBindAction,
+        AbsolutePos2D, AutoRepeat, Bind, BindAction, BindTrigger, ClickAtBindAction,
+        ClipboardBindAction, CommandBindAction, CommandRelease, Config, ContinuousMotion,
+        ContinuousMoveBindAction, ContinuousScrollBindAction, CycleBindAction, DragBindAction,
+        Easing, FeedbackConfig, GestureConfig, Glide, GlideAbsoluteBindAction,
+        GlideRelativeBindAction, HttpBindAction, HttpMethod, InputTransform, KeyboardKeyBindAction,
+        Layer, LayerActivation, LimitConfig, MacroBindAction, MidiOutMessage, MonitorChoice,
+        MonitorPos2D, RandomBindAction, RelativePos2D, ScrollBindAction, SendMidiBindAction,
+        SendOscBindAction, TextBindAction, ThruMessages, ThruRoute, VelocityCurve, BASE_LAYER_NAME,
     },
+    http::check_url,
+    key::{Key, KeyCombo, Modifier, NamedKey, MAX_FUNCTION_KEY},
+    monitor::Monitors,
     note::Note,
-    state::{manager::StateManager, table_data_adaptor::Adaptor, State},
+    osc::{format_osc_arguments, parse_osc_arguments, OscSender},
+    state::{
+        interface::StateInterface, manager::StateManager, midi_out::VIRTUAL_PORT_NAME,
+        table_data_adaptor::Adaptor, State,
+    },
+    step::{
+        format_actions, format_macro_steps, format_weighted_actions, parse_actions,
+        parse_macro_steps, parse_weighted_actions,
+    },
+    text::parse_text,
 };
 
+mod allowlist;
 mod bind;
+mod clipboard;
 mod config;
+mod held;
+mod http;
+mod key;
+mod monitor;
 mod note;
+mod osc;
 mod state;
+mod step;
+mod template;
+mod text;
 mod utils;
 
 fn main() -> anyhow::Result<()> {
@@ -33,6 +63,7 @@ fn main() -> anyhow::Result<()> {
         .config_dir()
         .to_path_buf();
     let config_file_path = config_dir.join("config.json");
+    let allowlist_file_path = config_dir.join("allowed_commands.json");
 
     // Attempt to deserialize into a Config, or fallback on creating a new Config
     let config: Config = match read_to_string(&config_file_path) {
@@ -43,7 +74,22 @@ fn main() -> anyhow::Result<()> {
         Err(_) => Config::new(),
     };
 
-    let state = State::from_config(config);
+    let state = State::from_config(config, CommandAllowlist::load(&allowlist_file_path));
+
+    // Don't leave keys stuck down if something goes wrong
+    let held_inputs = state.held_inputs();
+    let default_panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = Executor::new(
+            &held_inputs,
+            &ScrollRemainder::default(),
+            &Monitors::default(),
+            &OscSender::default(),
+        )
+        .release_everything();
+        default_panic_hook(info);
+    }));
+
     let (state_manager, state_interface) = StateManager::new(state);
     let _manager_thread = std::thread::spawn(move || {
         let mut state_manager = state_manager;
@@ -53,18 +99,127 @@ fn main() -> anyhow::Result<()> {
 
     let ui = UI::init()?;
 
+    let mut window = Window::new(&ui, "bindmidi", 600, 400, WindowType::NoMenubar);
+
     libui::layout! { &ui,
         let layout = HorizontalBox(padded: true) {
             Stretchy: let controls_wrapper = VerticalBox(padded: true) {
                 Compact: let label_status = Label("Status: Not Running")
                 Compact: let bt_start = Button("Start")
                 Compact: let bt_stop = Button("Stop")
+                Compact: let bt_panic = Button("Release Everything")
                 Compact: let sep_controls = HorizontalSeparator()
                 Compact: let form_midi = Form(padded: true) {
                     (Compact, "MIDI Input"): let combobox_midi_input = Combobox() {
                         // Filled at runtime with the available MIDI inputs
                     }
                 }
+                Compact: let sep_feedback = HorizontalSeparator()
+                Compact: let label_feedback = Label("Controller Feedback")
+                Compact: let form_feedback = Form(padded: true) {
+                    (Compact, "Output"): let combobox_feedback_output = Combobox() {
+                        // Filled at runtime with the available MIDI outputs
+                    }
+                    (Compact, "Channel"): let spinbox_feedback_channel = Spinbox(1, 16)
+                    (Compact, "Lit Velocity"): let spinbox_feedback_lit_velocity = Spinbox(0, 127)
+                    (Compact, "Unlit Velocity"): let spinbox_feedback_unlit_velocity = Spinbox(0, 127)
+                }
+                Compact: let sep_held = HorizontalSeparator()
+                Compact: let label_held = Label("Held")
+                Compact: let form_held = Form(padded: true) {
+                    (Compact, "Notes"): let label_held_notes = Label("None")
+                    (Compact, "Keys and Buttons"): let label_held_inputs = Label("None")
+                }
+                Compact: let form_panic = Form(padded: true) {
+                    (Compact, "Panic Note"): let checkbox_panic_note = Checkbox("Release everything when played")
+                    (Compact, "Note"): let combobox_panic_note = Combobox(selected: 0) {
+                        "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"
+                    }
+                    (Compact, "Octave"): let spinbox_panic_octave = Spinbox(-1, 8)
+                }
+                Compact: let sep_gestures = HorizontalSeparator()
+                Compact: let label_gestures = Label("Gestures")
+                Compact: let form_gestures = Form(padded: true) {
+                    (Compact, "Double Tap Gap (ms)"): let spinbox_double_tap_ms = Spinbox(0, 5000)
+                    (Compact, "Long Press Time (ms)"): let spinbox_long_press_ms = Spinbox(0, 10000)
+                }
+                Compact: let sep_limits = HorizontalSeparator()
+                Compact: let label_limits = Label("Rate Limit")
+                Compact: let form_limits = Form(padded: true) {
+                    (Compact, "Max Actions per Second"): let spinbox_max_actions_per_second = Spinbox(0, 1000)
+                    (Compact, "Max Hold Time (ms)"): let spinbox_max_hold_ms = Spinbox(0, 3_600_000)
+                }
+                Compact: let sep_layers = HorizontalSeparator()
+                Compact: let label_layers = Label("Layers")
+                Compact: let form_layers = Form(padded: true) {
+                    (Compact, "Layer"): let combobox_layer = Combobox() {
+                        // Filled at runtime with the configured layers
+                    }
+                }
+                Compact: let form_edit_layer = Form(padded: true) {
+                    (Compact, "Name"): let text_layer_name = Entry()
+                    (Compact, "Active"): let combobox_layer_activation = Combobox(selected: 0) {
+                        "While Sustain Held", "While Note Held", "Toggled by Note"
+                    }
+
+                    // Used for: While Note Held, Toggled by Note
+                    (Compact, "Note"): let combobox_layer_note = Combobox(selected: 0) {
+                        "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"
+                    }
+                    (Compact, "Octave"): let spinbox_layer_octave = Spinbox(-1, 8)
+                }
+                Compact: let container_layer_edit_buttons = HorizontalBox(padded: true) {
+                    Stretchy: let bt_add_layer = Button("New")
+                    Stretchy: let bt_update_layer = Button("Save")
+                    Stretchy: let bt_delete_layer = Button("Delete")
+                }
+                Compact: let sep_transform = HorizontalSeparator()
+                Compact: let label_transform = Label("Input Transform")
+                Compact: let form_transform = Form(padded: true) {
+                    (Compact, "Transpose (semitones)"): let spinbox_transpose = Spinbox(-127, 127)
+                    (Compact, "Octave Shift"): let spinbox_octave_shift = Spinbox(-10, 10)
+                    (Compact, "Selected Input Transpose"): let spinbox_port_transpose = Spinbox(-127, 127)
+                    (Compact, "Note Remap"): let text_note_map = Entry()
+                    (Compact, "Channel Remap"): let text_channel_map = Entry()
+                    (Compact, "Velocity Curve"): let combobox_velocity_curve = Combobox(selected: 0) {
+                        "Linear", "Fixed", "Compressed", "Expanded", "Custom"
+                    }
+
+                    // Used for: Fixed
+                    (Compact, "Velocity"): let spinbox_fixed_velocity = Spinbox(1, 127)
+
+                    // Used for: Custom
+                    (Compact, "Curve Points"): let text_velocity_points = Entry()
+                }
+                Compact: let bt_update_transform = Button("Save")
+                Compact: let sep_thru = HorizontalSeparator()
+                Compact: let label_thru = Label("MIDI Thru")
+                Compact: let form_thru = Form(padded: true) {
+                    (Compact, "Route"): let combobox_thru_route = Combobox() {
+                        // Filled at runtime with the configured routes
+                    }
+                }
+                Compact: let form_edit_thru = Form(padded: true) {
+                    (Compact, "From Input"): let text_thru_input = Entry()
+                    (Compact, "To Output"): let text_thru_output = Entry()
+                    (Compact, "Channels"): let text_thru_channels = Entry()
+                    (Compact, "Messages"): let checkbox_thru_notes = Checkbox("Notes")
+                    (Compact, ""): let checkbox_thru_control_changes = Checkbox("Control Changes")
+                    (Compact, ""): let checkbox_thru_program_changes = Checkbox("Program Changes")
+                    (Compact, ""): let checkbox_thru_pitch_bend = Checkbox("Pitch Bend")
+                    (Compact, ""): let checkbox_thru_aftertouch = Checkbox("Aftertouch")
+                    (Compact, ""): let checkbox_thru_system = Checkbox("System, Clock and SysEx")
+                    (Compact, "Lowest Note"): let spinbox_thru_lowest_note = Spinbox(0, 127)
+                    (Compact, "Highest Note"): let spinbox_thru_highest_note = Spinbox(0, 127)
+                    (Compact, "Transpose (semitones)"): let spinbox_thru_transpose = Spinbox(-127, 127)
+                    (Compact, "Send on Channel (0 keeps it)"): let spinbox_thru_output_channel = Spinbox(0, 16)
+                    (Compact, "Bound Notes"): let checkbox_thru_skip_bound_notes = Checkbox("Don't forward notes used by binds")
+                }
+                Compact: let container_thru_edit_buttons = HorizontalBox(padded: true) {
+                    Stretchy: let bt_add_thru_route = Button("New")
+                    Stretchy: let bt_update_thru_route = Button("Save")
+                    Stretchy: let bt_delete_thru_route = Button("Delete")
+                }
             }
             Stretchy: let config_wrapper = VerticalBox(padded: true) {
                 Compact: let label_table_binds = Label("Configured Binds")
@@ -79,37 +234,153 @@ fn main() -> anyhow::Result<()> {
                         "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"
                     }
                     (Compact, "Octave"): let spinbox_bind_octave = Spinbox(-1, 8)
+                    (Compact, "Trigger"): let combobox_bind_trigger = Combobox(selected: 0) {
+                        "Press", "Tap", "Double Tap", "Long Press (Acts on Release)"
+                    }
+                    (Compact, "Layer"): let combobox_bind_layer = Combobox() {
+                        // Filled at runtime with the configured layers
+                    }
+                    (Compact, "Exclusive Group"): let text_bind_group = Entry()
+                    (Compact, "Debounce (ms)"): let spinbox_bind_debounce_ms = Spinbox(0, 10000)
+                    (Compact, "Cooldown (ms)"): let spinbox_bind_cooldown_ms = Spinbox(0, 3_600_000)
+                    (Compact, "Max Hold Time (ms)"): let spinbox_bind_max_hold_ms = Spinbox(0, 3_600_000)
+                    (Compact, "Auto Repeat"): let checkbox_bind_auto_repeat = Checkbox("Repeat while held")
+                    (Compact, "Repeat Delay (ms)"): let spinbox_bind_repeat_delay_ms = Spinbox(0, 10000)
+                    (Compact, "Repeat Interval (ms)"): let spinbox_bind_repeat_interval_ms = Spinbox(1, 10000)
                     (Compact, "Action"): let combobox_bind_action = Combobox(selected: 0) {
-                        "Press Key", "Hold Key", "Click", "Hold Click", "Move Mouse" /* Relative */, "Move Mouse to" /* Absolute */, "Scroll", "Text" /* Types arbitrary text */, "Debug"
+                        "Press Key", "Hold Key", "Click", "Hold Click", "Move Mouse" /* Relative */, "Move Mouse to" /* Absolute */, "Scroll", "Text" /* Types arbitrary text */, "Macro", "Move Mouse Continuously", "Scroll Continuously", "Run Command", "Send MIDI", "Send OSC", "HTTP Request", "Clipboard", "Drag", "Click at", "Glide Mouse" /* Relative */, "Glide Mouse to" /* Absolute */, "Cycle", "Random", "Debug"
                     }
 
                     // The following are to be shown/hidden at runtime based on selected action in `combobox_bind_action`
                     // All possible action-specific config values are defined here:
 
                     // Used for: Press Key, Hold Key
-                    (Compact, "Key"): let text_bind_action_key = Entry()
+                    (Compact, "Key"): let combobox_bind_action_key = Combobox() {
+                        // Filled at runtime with the keys which can be picked
+                    }
+                    (Compact, "Character"): let text_bind_action_key_char = Entry()
+                    (Compact, "Keycode"): let spinbox_bind_action_keycode = Spinbox(0, 65535)
+                    (Compact, "Modifiers"): let container_bind_action_modifiers = HorizontalBox(padded: true) {
+                        Compact: let checkbox_bind_action_ctrl = Checkbox("Ctrl")
+                        Compact: let checkbox_bind_action_shift = Checkbox("Shift")
+                        Compact: let checkbox_bind_action_alt = Checkbox("Alt")
+                        Compact: let checkbox_bind_action_meta = Checkbox("Meta")
+                    }
+
+                    // Used for: Hold Key, Hold Click
+                    (Compact, "Sustain Pedal"): let checkbox_bind_sustain = Checkbox("Keep held while sustained")
+                    (Compact, "Toggle"): let checkbox_bind_toggle = Checkbox("Press again to release")
+                    (Compact, "Feedback"): let checkbox_bind_feedback = Checkbox("Light up the pad while held or latched")
 
-                    // Used for: Click, Hold Click
+                    // Used for: Click, Hold Click, Drag, Click at
                     (Compact, "Mouse Button"): let combobox_bind_action_mousebutton = Combobox(selected: 0) {
-                        "Left", "Right", "Middle"
+                        "Left", "Right", "Middle", "Back", "Forward"
                     }
 
-                    // Used for Move Mouse
+                    // Used for: Move Mouse, Move Mouse Continuously, Glide Mouse
                     (Compact, "x Amount (px)"): let spinbox_bind_action_xpixels = Spinbox()
                     (Compact, "y Amount (px)"): let spinbox_bind_action_ypixels = Spinbox()
 
-                    // Used for Move Mouse to
+                    // Used for Move Mouse to, Drag, Click at, Glide Mouse to
+                    (Compact, "Position In"): let combobox_bind_action_pos_unit = Combobox(selected: 0) {
+                        "Pixels", "% of the Cursor's Monitor", "% of the Main Monitor", "% of a Numbered Monitor", "% of a Named Monitor"
+                    }
+                    (Compact, "Monitor"): let text_bind_action_pos_monitor = Entry()
                     (Compact, "x Position (px)"): let spinbox_bind_action_xpos = Spinbox(0, i32::MAX)
                     (Compact, "y Position (px)"): let spinbox_bind_action_ypos = Spinbox(0, i32::MAX)
+                    (Compact, "x Position (%)"): let text_bind_action_xpercent = Entry()
+                    (Compact, "y Position (%)"): let text_bind_action_ypercent = Entry()
+
+                    // Used for: Drag
+                    (Compact, "Start x Position (px)"): let spinbox_bind_action_drag_xstart = Spinbox(0, i32::MAX)
+                    (Compact, "Start y Position (px)"): let spinbox_bind_action_drag_ystart = Spinbox(0, i32::MAX)
+                    (Compact, "Start x Position (%)"): let text_bind_action_drag_xstart_percent = Entry()
+                    (Compact, "Start y Position (%)"): let text_bind_action_drag_ystart_percent = Entry()
+
+                    // Used for: Click at
+                    (Compact, "Cursor"): let checkbox_bind_action_clickat_return = Checkbox("Put the cursor back afterwards")
+
+                    // Used for: Drag, Glide Mouse, Glide Mouse to
+                    (Compact, "Glide Time (ms)"): let spinbox_bind_action_glide_duration_ms = Spinbox(0, 60000)
+                    (Compact, "Easing"): let combobox_bind_action_glide_easing = Combobox(selected: 3) {
+                        "Linear", "Ease In", "Ease Out", "Ease In and Out"
+                    }
 
-                    // Used for: Scroll
+                    // Used for: Scroll, Scroll Continuously
                     (Compact, "Scroll Direction"): let combobox_bind_action_scrolldirection = Combobox(selected: 0) {
                         "Up", "Down", "Left", "Right"
                     }
                     (Compact, "Scroll Amount"): let spinbox_bind_action_scrollamount = Spinbox(0, i32::MAX)
+                    (Compact, "Precise"): let checkbox_bind_action_scroll_precise = Checkbox("Scroll by hundredths of a line")
+
+                    // Used for: Move Mouse Continuously, Scroll Continuously
+                    (Compact, "Interval (ms)"): let spinbox_bind_action_motion_interval_ms = Spinbox(1, 1000)
+                    (Compact, "Acceleration (%/s)"): let spinbox_bind_action_motion_acceleration = Spinbox(0, 1000)
+                    (Compact, "Velocity"): let checkbox_bind_action_motion_velocity = Checkbox("Move faster when played harder")
 
                     // Used for: Text
                     (Compact, "Text"): let text_bind_action_text = Entry()
+                    (Compact, "Character Delay (ms)"): let spinbox_bind_action_text_char_delay_ms = Spinbox(0, 10000)
+
+                    // Used for: Macro
+                    (Stretchy, "Steps"): let text_bind_action_macro_steps = MultilineEntry()
+                    (Compact, "Repeat"): let spinbox_bind_action_macro_repeat = Spinbox(0, 10000)
+                    (Compact, "Note Release"): let checkbox_bind_action_macro_cancel = Checkbox("Stop the macro when the note ends")
+
+                    // Used for: Run Command
+                    (Compact, "Program"): let text_bind_action_command_program = Entry()
+                    (Stretchy, "Arguments"): let text_bind_action_command_args = MultilineEntry()
+                    (Compact, "Working Directory"): let text_bind_action_command_dir = Entry()
+                    (Stretchy, "Environment"): let text_bind_action_command_env = MultilineEntry()
+                    (Compact, "Note Release"): let combobox_bind_action_command_release = Combobox(selected: 0) {
+                        "Leave Running", "Leave Running, but Don't Rerun Until It Exits", "Stop the Program"
+                    }
+                    (Compact, "Allowed"): let checkbox_bind_action_command_allowed = Checkbox("Allow this command to run on this computer")
+
+                    // Used for: Send MIDI
+                    (Compact, "Output Port"): let text_bind_action_midi_port = Entry()
+                    (Compact, "Message"): let combobox_bind_action_midi_message = Combobox(selected: 0) {
+                        "Note", "Control Change", "Program Change", "Raw Bytes"
+                    }
+                    (Compact, "Channel"): let spinbox_bind_action_midi_channel = Spinbox(1, 16)
+                    (Compact, "Number"): let spinbox_bind_action_midi_number = Spinbox(0, 127)
+                    (Compact, "Value"): let spinbox_bind_action_midi_value = Spinbox(0, 127)
+                    (Compact, "Bytes"): let text_bind_action_midi_bytes = Entry()
+
+                    // Used for: Send OSC
+                    (Compact, "Host"): let text_bind_action_osc_host = Entry()
+                    (Compact, "Port"): let spinbox_bind_action_osc_port = Spinbox(1, 65535)
+                    (Compact, "Address"): let text_bind_action_osc_address = Entry()
+                    (Stretchy, "Arguments"): let text_bind_action_osc_args = MultilineEntry()
+
+                    // Used for: HTTP Request
+                    (Compact, "Method"): let combobox_bind_action_http_method = Combobox(selected: 0) {
+                        "GET", "POST", "PUT"
+                    }
+                    (Compact, "URL"): let text_bind_action_http_url = Entry()
+                    (Stretchy, "Headers"): let text_bind_action_http_headers = MultilineEntry()
+                    (Stretchy, "Body"): let text_bind_action_http_body = MultilineEntry()
+                    (Compact, "Timeout (ms)"): let spinbox_bind_action_http_timeout_ms = Spinbox(1, 60000)
+
+                    // Used for: Clipboard
+                    (Stretchy, "Clipboard Text"): let text_bind_action_clipboard_text = MultilineEntry()
+                    (Compact, "Paste"): let checkbox_bind_action_clipboard_paste = Checkbox("Paste it")
+                    (Compact, "Restore"): let checkbox_bind_action_clipboard_restore = Checkbox("Put back what was on the clipboard after pasting")
+
+                    // Used for: Cycle
+                    (Stretchy, "Actions"): let text_bind_action_cycle_actions = MultilineEntry()
+                    (Compact, "After the Last"): let checkbox_bind_action_cycle_wrap = Checkbox("Go back to the first action")
+                    (Compact, "Reset After (ms)"): let spinbox_bind_action_cycle_reset_ms = Spinbox(0, i32::MAX)
+                    (Compact, "Reset on Start"): let checkbox_bind_action_cycle_reset_on_connect = Checkbox("Go back to the first action when started")
+                    (Compact, "Remember"): let checkbox_bind_action_cycle_persist = Checkbox("Carry on from the same action after restarting bindmidi")
+
+                    // Used for: Random
+                    (Stretchy, "Weighted Actions"): let text_bind_action_random_actions = MultilineEntry()
+                    (Compact, "Repeats"): let checkbox_bind_action_random_no_repeat = Checkbox("Never pick the same action twice in a row")
+
+                    // Used for: every action
+                    (Compact, "Release Action"): let text_bind_release_action = Entry()
+                    (Compact, ""): let label_bind_release_action_syntax = Label("Written like a macro step: press Ctrl+C, hold Shift, click right, holdclick left, move 10 -5,\nmoveto 100 200, clickat 50% 50% @primary right, drag 0 0 100 200 left, scroll down 1.5, text Hello or debug")
                 }
                 Compact: let container_bind_edit_buttons = HorizontalBox(padded: true) {
                     Stretchy: let bt_update_bind = Button("Save")
@@ -126,13 +397,27 @@ fn main() -> anyhow::Result<()> {
 
     table_binds.append_text_column("Note", 0, Table::COLUMN_READONLY);
     table_binds.append_text_column("Action", 1, Table::COLUMN_READONLY);
+    table_binds.append_text_column("Trigger", 2, Table::COLUMN_READONLY);
+    table_binds.append_text_column("Layer", 3, Table::COLUMN_READONLY);
+    table_binds.append_text_column("Toggle", 4, Table::COLUMN_READONLY);
 
     container_table_binds.append(table_binds.clone(), LayoutStrategy::Stretchy);
 
+    // Fill the key picker with every key which can be picked
+    for name in key_picker_names() {
+        combobox_bind_action_key.append(&name);
+    }
+    combobox_bind_action_key.set_selected(0);
+
     // Show/hide action-specific config based on selected bind action
     let mut clean_bind_action_config = {
         shadow_clone_mut!(
-            text_bind_action_key,
+            combobox_bind_action_key,
+            text_bind_action_key_char,
+            spinbox_bind_action_keycode,
+            container_bind_action_modifiers,
+            checkbox_bind_sustain,
+            checkbox_bind_toggle,
             combobox_bind_action_mousebutton,
             spinbox_bind_action_xpixels,
             spinbox_bind_action_ypixels,
@@ -140,25 +425,315 @@ fn main() -> anyhow::Result<()> {
             spinbox_bind_action_ypos,
             combobox_bind_action_scrolldirection,
             spinbox_bind_action_scrollamount,
-            text_bind_action_text
+            spinbox_bind_action_motion_interval_ms,
+            spinbox_bind_action_motion_acceleration,
+            checkbox_bind_action_motion_velocity,
+            text_bind_action_text,
+            spinbox_bind_action_text_char_delay_ms,
+            text_bind_action_macro_steps,
+            spinbox_bind_action_macro_repeat,
+            checkbox_bind_action_macro_cancel,
+            text_bind_action_command_program,
+            text_bind_action_command_args,
+            text_bind_action_command_dir,
+            text_bind_action_command_env,
+            combobox_bind_action_command_release,
+            checkbox_bind_action_command_allowed,
+            text_bind_action_midi_port,
+            combobox_bind_action_midi_message,
+            spinbox_bind_action_midi_channel,
+            spinbox_bind_action_midi_number,
+            spinbox_bind_action_midi_value,
+            text_bind_action_midi_bytes,
+            text_bind_action_osc_host,
+            spinbox_bind_action_osc_port,
+            text_bind_action_osc_address,
+            text_bind_action_osc_args,
+            combobox_bind_action_http_method,
+            text_bind_action_http_url,
+            text_bind_action_http_headers,
+            text_bind_action_http_body,
+            spinbox_bind_action_http_timeout_ms,
+            text_bind_action_clipboard_text,
+            checkbox_bind_action_clipboard_paste,
+            checkbox_bind_action_clipboard_restore,
+            spinbox_bind_action_drag_xstart,
+            spinbox_bind_action_drag_ystart,
+            checkbox_bind_action_clickat_return,
+            spinbox_bind_action_glide_duration_ms,
+            combobox_bind_action_glide_easing,
+            combobox_bind_action_pos_unit,
+            text_bind_action_pos_monitor,
+            text_bind_action_xpercent,
+            text_bind_action_ypercent,
+            text_bind_action_drag_xstart_percent,
+            text_bind_action_drag_ystart_percent,
+            checkbox_bind_action_scroll_precise,
+            text_bind_action_cycle_actions,
+            checkbox_bind_action_cycle_wrap,
+            spinbox_bind_action_cycle_reset_ms,
+            checkbox_bind_action_cycle_reset_on_connect,
+            checkbox_bind_action_cycle_persist,
+            text_bind_action_random_actions,
+            checkbox_bind_action_random_no_repeat
         );
 
         move |selected| {
             show_control_only_when!(selected,
-                text_bind_action_key: 0 | 1,
-                combobox_bind_action_mousebutton: 2 | 3,
-                spinbox_bind_action_xpixels: 4,
-                spinbox_bind_action_ypixels: 4,
-                spinbox_bind_action_xpos: 5,
-                spinbox_bind_action_ypos: 5,
-                combobox_bind_action_scrolldirection: 6,
-                spinbox_bind_action_scrollamount: 6,
-                text_bind_action_text: 7
+                container_bind_action_modifiers: 0 | 1,
+                checkbox_bind_sustain: 1 | 3,
+                checkbox_bind_toggle: 1 | 3,
+                combobox_bind_action_mousebutton: 2 | 3 | 16 | 17,
+                spinbox_bind_action_xpixels: 4 | 9 | 18,
+                spinbox_bind_action_ypixels: 4 | 9 | 18,
+                checkbox_bind_action_clickat_return: 17,
+                spinbox_bind_action_glide_duration_ms: 16 | 18 | 19,
+                combobox_bind_action_glide_easing: 16 | 18 | 19,
+                combobox_bind_action_scrolldirection: 6 | 10,
+                spinbox_bind_action_scrollamount: 6 | 10,
+                checkbox_bind_action_scroll_precise: 6 | 10,
+                spinbox_bind_action_motion_interval_ms: 9 | 10,
+                spinbox_bind_action_motion_acceleration: 9 | 10,
+                checkbox_bind_action_motion_velocity: 9 | 10,
+                text_bind_action_text: 7,
+                spinbox_bind_action_text_char_delay_ms: 7,
+                text_bind_action_macro_steps: 8,
+                spinbox_bind_action_macro_repeat: 8,
+                checkbox_bind_action_macro_cancel: 8,
+                text_bind_action_command_program: 11,
+                text_bind_action_command_args: 11,
+                text_bind_action_command_dir: 11,
+                text_bind_action_command_env: 11,
+                combobox_bind_action_command_release: 11,
+                checkbox_bind_action_command_allowed: 11,
+                text_bind_action_midi_port: 12,
+                combobox_bind_action_midi_message: 12,
+                text_bind_action_osc_host: 13,
+                spinbox_bind_action_osc_port: 13,
+                text_bind_action_osc_address: 13,
+                text_bind_action_osc_args: 13,
+                combobox_bind_action_http_method: 14,
+                text_bind_action_http_url: 14,
+                text_bind_action_http_headers: 14,
+                spinbox_bind_action_http_timeout_ms: 14,
+                text_bind_action_clipboard_text: 15,
+                checkbox_bind_action_clipboard_paste: 15,
+                checkbox_bind_action_clipboard_restore: 15,
+                text_bind_action_cycle_actions: 20,
+                checkbox_bind_action_cycle_wrap: 20,
+                spinbox_bind_action_cycle_reset_ms: 20,
+                checkbox_bind_action_cycle_reset_on_connect: 20,
+                checkbox_bind_action_cycle_persist: 20,
+                text_bind_action_random_actions: 21,
+                checkbox_bind_action_random_no_repeat: 21
+            );
+
+            // Positions are in pixels, or percentages of a monitor which can be picked
+            let pos_unit = match selected {
+                5 | 16 | 17 | 19 => combobox_bind_action_pos_unit.selected(),
+                _ => -1,
+            };
+            show_control_only_when!(pos_unit,
+                combobox_bind_action_pos_unit: 0..,
+                text_bind_action_pos_monitor: 3 | 4,
+                spinbox_bind_action_xpos: 0,
+                spinbox_bind_action_ypos: 0,
+                text_bind_action_xpercent: 1..=4,
+                text_bind_action_ypercent: 1..=4
+            );
+
+            // Drags start in the same unit as they end
+            let drag_unit = match selected {
+                16 => combobox_bind_action_pos_unit.selected(),
+                _ => -1,
+            };
+            show_control_only_when!(drag_unit,
+                spinbox_bind_action_drag_xstart: 0,
+                spinbox_bind_action_drag_ystart: 0,
+                text_bind_action_drag_xstart_percent: 1..=4,
+                text_bind_action_drag_ystart_percent: 1..=4
+            );
+
+            // Only requests which send a body show it
+            let http_method = match selected {
+                14 => combobox_bind_action_http_method.selected(),
+                _ => -1,
+            };
+            show_control_only_when!(http_method,
+                text_bind_action_http_body: 1 | 2
+            );
+
+            // The message picker is shown alongside the boxes for whichever kind of message is picked
+            let midi_message = match selected {
+                12 => combobox_bind_action_midi_message.selected(),
+                _ => -1,
+            };
+            show_control_only_when!(midi_message,
+                spinbox_bind_action_midi_channel: 0..=2,
+                spinbox_bind_action_midi_number: 0..=2,
+                spinbox_bind_action_midi_value: 0 | 1,
+                text_bind_action_midi_bytes: 3
             );
+
+            // The key picker is shown alongside the box for whichever kind of key is picked
+            let key_picker = match selected {
+                0 | 1 => combobox_bind_action_key.selected(),
+                _ => -1,
+            };
+            show_control_only_when!(key_picker,
+                combobox_bind_action_key: 0..,
+                text_bind_action_key_char: 0,
+                spinbox_bind_action_keycode: 1
+            );
+        }
+    };
+
+    // Asks before allowing a command to run, as it can do anything the user can
+    let mut window_allow_command =
+        Window::new(&ui, "Allow this command?", 400, 150, WindowType::NoMenubar);
+    libui::layout! { &ui,
+        let layout_allow_command = VerticalBox(padded: true) {
+            Stretchy: let label_allow_command = Label("")
+            Compact: let container_allow_command_buttons = HorizontalBox(padded: true) {
+                Stretchy: let bt_allow_command = Button("Allow")
+                Stretchy: let bt_disallow_command = Button("Don't Allow")
+            }
+        }
+    }
+    window_allow_command.set_margined(true);
+    window_allow_command.set_child(layout_allow_command);
+    window_allow_command.on_closing(&ui, Window::hide);
+
+    // Untick allowing the command, as what it was ticked for has changed
+    let reset_command_allowed = {
+        shadow_clone_mut!(checkbox_bind_action_command_allowed, window_allow_command);
+
+        move || {
+            checkbox_bind_action_command_allowed.set_checked(false);
+            window_allow_command.hide();
         }
     };
+
+    checkbox_bind_action_command_allowed.on_toggled(&ui, {
+        shadow_clone!(text_bind_action_command_program, text_bind_action_command_args);
+        shadow_clone_mut!(
+            checkbox_bind_action_command_allowed,
+            window_allow_command,
+            label_allow_command
+        );
+
+        move |checked| {
+            if !checked {
+                window_allow_command.hide();
+                return;
+            }
+
+            // Only ticked once it's been confirmed
+            checkbox_bind_action_command_allowed.set_checked(false);
+
+            let args = text_bind_action_command_args.value();
+            let args: Vec<&str> = args.lines().filter(|x| !x.trim().is_empty()).collect();
+            label_allow_command.set_text(&format!(
+                "Allow `{}` to run{} whenever this bind acts?\n\nIt can do anything you can, so only allow commands you trust, especially from a config someone else shared.",
+                text_bind_action_command_program.value().trim(),
+                if args.is_empty() {
+                    String::new()
+                } else {
+                    format!(" with `{}`", args.join(" "))
+                }
+            ));
+            window_allow_command.show();
+        }
+    });
+    bt_allow_command.on_clicked({
+        shadow_clone_mut!(checkbox_bind_action_command_allowed, window_allow_command);
+
+        move |_| {
+            checkbox_bind_action_command_allowed.set_checked(true);
+            window_allow_command.hide();
+        }
+    });
+    bt_disallow_command.on_clicked({
+        shadow_clone_mut!(reset_command_allowed);
+
+        move |_| reset_command_allowed()
+    });
+
+    // What's allowed is exactly what's run, so changing any of it has to be allowed again
+    for entry in [
+        &mut text_bind_action_command_program,
+        &mut text_bind_action_command_dir,
+    ] {
+        entry.on_changed({
+            shadow_clone_mut!(reset_command_allowed);
+
+            move |_| reset_command_allowed()
+        });
+    }
+    for entry in [
+        &mut text_bind_action_command_args,
+        &mut text_bind_action_command_env,
+    ] {
+        entry.on_changed({
+            shadow_clone_mut!(reset_command_allowed);
+
+            move |_| reset_command_allowed()
+        });
+    }
+
     (clean_bind_action_config(0)); // Run once at startup
-    combobox_bind_action.on_selected(&ui, clean_bind_action_config.clone());
+    combobox_bind_action.on_selected(&ui, {
+        shadow_clone_mut!(clean_bind_action_config, reset_command_allowed);
+
+        move |x| {
+            reset_command_allowed();
+            clean_bind_action_config(x);
+        }
+    });
+    combobox_bind_action_key.on_selected(&ui, {
+        shadow_clone!(combobox_bind_action);
+        shadow_clone_mut!(clean_bind_action_config);
+
+        move |_| clean_bind_action_config(combobox_bind_action.selected())
+    });
+
+    combobox_bind_action_midi_message.on_selected(&ui, {
+        shadow_clone!(combobox_bind_action);
+        shadow_clone_mut!(clean_bind_action_config);
+
+        move |_| clean_bind_action_config(combobox_bind_action.selected())
+    });
+
+    combobox_bind_action_http_method.on_selected(&ui, {
+        shadow_clone!(combobox_bind_action);
+        shadow_clone_mut!(clean_bind_action_config);
+
+        move |_| clean_bind_action_config(combobox_bind_action.selected())
+    });
+
+    combobox_bind_action_pos_unit.on_selected(&ui, {
+        shadow_clone!(combobox_bind_action);
+        shadow_clone_mut!(clean_bind_action_config);
+
+        move |_| clean_bind_action_config(combobox_bind_action.selected())
+    });
+
+    // Show/hide auto-repeat timings based on whether auto-repeat is on
+    let mut clean_auto_repeat_config = {
+        shadow_clone_mut!(
+            spinbox_bind_repeat_delay_ms,
+            spinbox_bind_repeat_interval_ms
+        );
+
+        move |checked| {
+            show_control_only_when!(checked,
+                spinbox_bind_repeat_delay_ms: true,
+                spinbox_bind_repeat_interval_ms: true
+            );
+        }
+    };
+    (clean_auto_repeat_config(false)); // Run once at startup
+    checkbox_bind_auto_repeat.on_toggled(&ui, clean_auto_repeat_config.clone());
 
     // Enable bind-editing GUI only if a bind is selected, otherwise disable
     let mut enable_bind_edit_only_if_needed = {
@@ -176,13 +751,31 @@ fn main() -> anyhow::Result<()> {
 
     // Update data and edit form when bind (de)selected in the table
     table_binds.on_selection_changed({
-        shadow_clone!(state_interface);
+        shadow_clone!(state_interface, window);
 
         shadow_clone_mut!(
             combobox_bind_note,
             spinbox_bind_octave,
+            combobox_bind_trigger,
+            combobox_bind_layer,
+            text_bind_group,
+            spinbox_bind_debounce_ms,
+            spinbox_bind_cooldown_ms,
+            spinbox_bind_max_hold_ms,
+            checkbox_bind_auto_repeat,
+            spinbox_bind_repeat_delay_ms,
+            spinbox_bind_repeat_interval_ms,
             combobox_bind_action,
-            text_bind_action_key,
+            combobox_bind_action_key,
+            text_bind_action_key_char,
+            spinbox_bind_action_keycode,
+            checkbox_bind_action_ctrl,
+            checkbox_bind_action_shift,
+            checkbox_bind_action_alt,
+            checkbox_bind_action_meta,
+            checkbox_bind_sustain,
+            checkbox_bind_toggle,
+            checkbox_bind_feedback,
             combobox_bind_action_mousebutton,
             spinbox_bind_action_xpixels,
             spinbox_bind_action_ypixels,
@@ -190,7 +783,59 @@ fn main() -> anyhow::Result<()> {
             spinbox_bind_action_ypos,
             combobox_bind_action_scrolldirection,
             spinbox_bind_action_scrollamount,
-            text_bind_action_text
+            spinbox_bind_action_motion_interval_ms,
+            spinbox_bind_action_motion_acceleration,
+            checkbox_bind_action_motion_velocity,
+            text_bind_action_text,
+            spinbox_bind_action_text_char_delay_ms,
+            text_bind_action_macro_steps,
+            spinbox_bind_action_macro_repeat,
+            checkbox_bind_action_macro_cancel,
+            text_bind_action_command_program,
+            text_bind_action_command_args,
+            text_bind_action_command_dir,
+            text_bind_action_command_env,
+            combobox_bind_action_command_release,
+            checkbox_bind_action_command_allowed,
+            text_bind_action_midi_port,
+            combobox_bind_action_midi_message,
+            spinbox_bind_action_midi_channel,
+            spinbox_bind_action_midi_number,
+            spinbox_bind_action_midi_value,
+            text_bind_action_midi_bytes,
+            text_bind_action_osc_host,
+            spinbox_bind_action_osc_port,
+            text_bind_action_osc_address,
+            text_bind_action_osc_args,
+            combobox_bind_action_http_method,
+            text_bind_action_http_url,
+            text_bind_action_http_headers,
+            text_bind_action_http_body,
+            spinbox_bind_action_http_timeout_ms,
+            text_bind_action_clipboard_text,
+            checkbox_bind_action_clipboard_paste,
+            checkbox_bind_action_clipboard_restore,
+            spinbox_bind_action_drag_xstart,
+            spinbox_bind_action_drag_ystart,
+            checkbox_bind_action_clickat_return,
+            spinbox_bind_action_glide_duration_ms,
+            combobox_bind_action_glide_easing,
+            combobox_bind_action_pos_unit,
+            text_bind_action_pos_monitor,
+            text_bind_action_xpercent,
+            text_bind_action_ypercent,
+            text_bind_action_drag_xstart_percent,
+            text_bind_action_drag_ystart_percent,
+            checkbox_bind_action_scroll_precise,
+            text_bind_action_cycle_actions,
+            checkbox_bind_action_cycle_wrap,
+            spinbox_bind_action_cycle_reset_ms,
+            checkbox_bind_action_cycle_reset_on_connect,
+            checkbox_bind_action_cycle_persist,
+            text_bind_action_random_actions,
+            checkbox_bind_action_random_no_repeat,
+            text_bind_release_action,
+            reset_command_allowed
         );
 
         move |x| {
@@ -214,15 +859,96 @@ fn main() -> anyhow::Result<()> {
 
                 combobox_bind_note.set_selected(i32::from(bind.note.get_pitch_class_offset()));
                 spinbox_bind_octave.set_value(i32::from(bind.note.get_octave()));
+                combobox_bind_trigger.set_selected(i32::from(bind.trigger.index()));
+                combobox_bind_layer.set_selected(bind_layer_index(&state_interface, &bind));
+                text_bind_group.set_value(bind.group.as_deref().unwrap_or_default());
+                spinbox_bind_debounce_ms.set_value(bind.debounce_ms.try_into().unwrap());
+                spinbox_bind_cooldown_ms.set_value(bind.cooldown_ms.try_into().unwrap());
+                spinbox_bind_max_hold_ms.set_value(bind.max_hold_ms.try_into().unwrap());
+
+                checkbox_bind_auto_repeat.set_checked(bind.auto_repeat.is_some());
+                clean_auto_repeat_config(bind.auto_repeat.is_some());
+                let auto_repeat = bind.auto_repeat.unwrap_or_default();
+                spinbox_bind_repeat_delay_ms.set_value(auto_repeat.delay_ms.try_into().unwrap());
+                spinbox_bind_repeat_interval_ms
+                    .set_value(auto_repeat.interval_ms.try_into().unwrap());
+                show_steps(
+                    &window,
+                    &mut text_bind_release_action,
+                    bind.release_action
+                        .as_ref()
+                        .map_or(Some(String::new()), BindAction::to_step),
+                );
+                checkbox_bind_sustain.set_checked(bind.sustain);
+                checkbox_bind_toggle.set_checked(bind.toggle);
+                checkbox_bind_feedback.set_checked(bind.feedback);
+
+                // Only ticked again below if this bind runs a command which is allowed
+                reset_command_allowed();
 
                 let action = bind.action;
 
+                let mut show_motion = |motion: &ContinuousMotion| {
+                    spinbox_bind_action_motion_interval_ms
+                        .set_value(motion.interval_ms.try_into().unwrap_or(i32::MAX));
+                    spinbox_bind_action_motion_acceleration
+                        .set_value(motion.acceleration.try_into().unwrap_or(i32::MAX));
+                    checkbox_bind_action_motion_velocity.set_checked(motion.velocity_sensitive);
+                };
+                let mut show_glide = |glide: &Glide| {
+                    spinbox_bind_action_glide_duration_ms
+                        .set_value(glide.duration_ms.try_into().unwrap_or(i32::MAX));
+                    combobox_bind_action_glide_easing.set_selected(i32::from(glide.easing.index()));
+                };
+                let mut show_position = |position: &AbsolutePos2D| {
+                    spinbox_bind_action_xpos.set_value(position.x);
+                    spinbox_bind_action_ypos.set_value(position.y);
+                    text_bind_action_xpercent.set_value(&format_percent(position, true));
+                    text_bind_action_ypercent.set_value(&format_percent(position, false));
+                };
+
+                // Every position of an action is in the same unit, which is picked first so the right boxes are shown
+                let position = match &action {
+                    Act::MoveMouseAbsolute(act) => Some(act),
+                    Act::Drag(act) => Some(&act.to),
+                    Act::ClickAt(act) => Some(&act.position),
+                    Act::GlideMouseAbsolute(act) => Some(&act.position),
+                    _ => None,
+                };
+                if let Some(position) = position {
+                    let monitor = position.monitor.as_ref().map(|x| &x.monitor);
+
+                    combobox_bind_action_pos_unit
+                        .set_selected(monitor.map_or(0, |x| i32::from(x.index()) + 1));
+                    text_bind_action_pos_monitor.set_value(&match monitor {
+                        Some(MonitorChoice::Numbered(x)) => x.to_string(),
+                        Some(MonitorChoice::Named(x)) => x.clone(),
+                        _ => String::new(),
+                    });
+                }
+
                 combobox_bind_action.set_selected(i32::from(action.index()));
                 clean_bind_action_config(combobox_bind_action.selected());
 
                 match action {
                     Act::PressKey(act) | Act::HoldKey(act) => {
-                        text_bind_action_key.set_value(&act.key);
+                        combobox_bind_action_key.set_selected(key_picker_index(&act.key.key));
+                        clean_bind_action_config(combobox_bind_action.selected());
+
+                        match act.key.key {
+                            Key::Char(x) => text_bind_action_key_char.set_value(&x.to_string()),
+                            Key::Raw(x) => spinbox_bind_action_keycode.set_value(i32::from(x)),
+                            Key::Named(_) | Key::Function(_) => {}
+                        }
+
+                        for (checkbox, modifier) in [
+                            (&mut checkbox_bind_action_ctrl, Modifier::Control),
+                            (&mut checkbox_bind_action_shift, Modifier::Shift),
+                            (&mut checkbox_bind_action_alt, Modifier::Alt),
+                            (&mut checkbox_bind_action_meta, Modifier::Meta),
+                        ] {
+                            checkbox.set_checked(act.key.modifiers.contains(&modifier));
+                        }
                     }
                     Act::Click(act) | Act::HoldClick(act) => {
                         combobox_bind_action_mousebutton.set_selected(i32::from(act.index()));
@@ -231,17 +957,149 @@ fn main() -> anyhow::Result<()> {
                         spinbox_bind_action_xpixels.set_value(act.x);
                         spinbox_bind_action_ypixels.set_value(act.y);
                     }
-                    Act::MoveMouseAbsolute(act) => {
-                        spinbox_bind_action_xpos.set_value(act.x);
-                        spinbox_bind_action_ypos.set_value(act.y);
-                    }
+                    Act::MoveMouseAbsolute(act) => show_position(&act),
                     Act::Scroll(act) => {
                         combobox_bind_action_scrolldirection
                             .set_selected(i32::from(act.direction.index()));
                         spinbox_bind_action_scrollamount.set_value(act.amount);
+                        checkbox_bind_action_scroll_precise.set_checked(act.precise);
                     }
                     Act::Text(act) => {
                         text_bind_action_text.set_value(&act.text);
+                        spinbox_bind_action_text_char_delay_ms
+                            .set_value(act.char_delay_ms.try_into().unwrap_or(i32::MAX));
+                    }
+                    Act::Macro(act) => {
+                        show_steps(
+                            &window,
+                            &mut text_bind_action_macro_steps,
+                            format_macro_steps(&act.steps),
+                        );
+                        spinbox_bind_action_macro_repeat
+                            .set_value(act.repeat.try_into().unwrap_or(i32::MAX));
+                        checkbox_bind_action_macro_cancel.set_checked(act.cancel_on_release);
+                    }
+                    Act::MoveMouseContinuous(act) => {
+                        spinbox_bind_action_xpixels.set_value(act.step.x);
+                        spinbox_bind_action_ypixels.set_value(act.step.y);
+                        show_motion(&act.motion);
+                    }
+                    Act::ScrollContinuous(act) => {
+                        combobox_bind_action_scrolldirection
+                            .set_selected(i32::from(act.step.direction.index()));
+                        spinbox_bind_action_scrollamount.set_value(act.step.amount);
+                        checkbox_bind_action_scroll_precise.set_checked(act.step.precise);
+                        show_motion(&act.motion);
+                    }
+                    Act::Command(act) => {
+                        text_bind_action_command_program.set_value(&act.program);
+                        text_bind_action_command_args.set_value(&act.args.join("\n"));
+                        text_bind_action_command_dir
+                            .set_value(act.working_dir.as_deref().unwrap_or_default());
+                        text_bind_action_command_env.set_value(&format_environment(&act.env));
+                        combobox_bind_action_command_release
+                            .set_selected(i32::from(act.on_release.index()));
+                        checkbox_bind_action_command_allowed
+                            .set_checked(state_interface.is_command_allowed(act));
+                    }
+                    Act::SendMidi(act) => {
+                        text_bind_action_midi_port
+                            .set_value(act.port.as_deref().unwrap_or_default());
+                        combobox_bind_action_midi_message
+                            .set_selected(i32::from(act.message.index()));
+                        clean_bind_action_config(combobox_bind_action.selected());
+
+                        let (channel, number, value) = match act.message {
+                            MidiOutMessage::Note {
+                                channel,
+                                note,
+                                velocity,
+                            } => (channel, note, velocity),
+                            MidiOutMessage::ControlChange {
+                                channel,
+                                controller,
+                                value,
+                            } => (channel, controller, value),
+                            MidiOutMessage::ProgramChange { channel, program } => {
+                                (channel, program, 0)
+                            }
+                            MidiOutMessage::Raw(bytes) => {
+                                text_bind_action_midi_bytes.set_value(&format_hex_bytes(&bytes));
+                                (0, 0, 0)
+                            }
+                        };
+                        spinbox_bind_action_midi_channel.set_value(i32::from(channel) + 1);
+                        spinbox_bind_action_midi_number.set_value(i32::from(number));
+                        spinbox_bind_action_midi_value.set_value(i32::from(value));
+                    }
+                    Act::SendOsc(act) => {
+                        text_bind_action_osc_host.set_value(&act.host);
+                        spinbox_bind_action_osc_port.set_value(i32::from(act.port));
+                        text_bind_action_osc_address.set_value(&act.address);
+                        text_bind_action_osc_args.set_value(&format_osc_arguments(&act.args));
+                    }
+                    Act::Http(act) => {
+                        combobox_bind_action_http_method
+                            .set_selected(i32::from(act.method.index()));
+                        clean_bind_action_config(combobox_bind_action.selected());
+                        text_bind_action_http_url.set_value(&act.url);
+                        text_bind_action_http_headers.set_value(&format_headers(&act.headers));
+                        text_bind_action_http_body.set_value(&act.body);
+                        spinbox_bind_action_http_timeout_ms
+                            .set_value(act.timeout_ms.try_into().unwrap_or(i32::MAX));
+                    }
+                    Act::Clipboard(act) => {
+                        text_bind_action_clipboard_text.set_value(&act.text);
+                        checkbox_bind_action_clipboard_paste.set_checked(act.paste);
+                        checkbox_bind_action_clipboard_restore.set_checked(act.restore);
+                    }
+                    Act::Drag(act) => {
+                        combobox_bind_action_mousebutton
+                            .set_selected(i32::from(act.button.index()));
+                        spinbox_bind_action_drag_xstart.set_value(act.from.x);
+                        spinbox_bind_action_drag_ystart.set_value(act.from.y);
+                        text_bind_action_drag_xstart_percent
+                            .set_value(&format_percent(&act.from, true));
+                        text_bind_action_drag_ystart_percent
+                            .set_value(&format_percent(&act.from, false));
+                        show_position(&act.to);
+                        show_glide(&act.glide);
+                    }
+                    Act::ClickAt(act) => {
+                        combobox_bind_action_mousebutton
+                            .set_selected(i32::from(act.button.index()));
+                        show_position(&act.position);
+                        checkbox_bind_action_clickat_return.set_checked(act.return_cursor);
+                    }
+                    Act::GlideMouseRelative(act) => {
+                        spinbox_bind_action_xpixels.set_value(act.offset.x);
+                        spinbox_bind_action_ypixels.set_value(act.offset.y);
+                        show_glide(&act.glide);
+                    }
+                    Act::GlideMouseAbsolute(act) => {
+                        show_position(&act.position);
+                        show_glide(&act.glide);
+                    }
+                    Act::Cycle(act) => {
+                        show_steps(
+                            &window,
+                            &mut text_bind_action_cycle_actions,
+                            format_actions(&act.actions),
+                        );
+                        checkbox_bind_action_cycle_wrap.set_checked(act.wrap);
+                        spinbox_bind_action_cycle_reset_ms
+                            .set_value(act.reset_after_ms.try_into().unwrap_or(i32::MAX));
+                        checkbox_bind_action_cycle_reset_on_connect
+                            .set_checked(act.reset_on_connect);
+                        checkbox_bind_action_cycle_persist.set_checked(act.persist);
+                    }
+                    Act::Random(act) => {
+                        show_steps(
+                            &window,
+                            &mut text_bind_action_random_actions,
+                            format_weighted_actions(&act.choices),
+                        );
+                        checkbox_bind_action_random_no_repeat.set_checked(act.no_repeat);
                     }
                     Act::Debug => {}
                 }
@@ -291,11 +1149,30 @@ fn main() -> anyhow::Result<()> {
     bt_update_bind.on_clicked({
         shadow_clone!(
             state_interface,
+            window,
             table_binds_model,
             combobox_bind_note,
             spinbox_bind_octave,
+            combobox_bind_trigger,
+            combobox_bind_layer,
+            text_bind_group,
+            spinbox_bind_debounce_ms,
+            spinbox_bind_cooldown_ms,
+            spinbox_bind_max_hold_ms,
+            checkbox_bind_auto_repeat,
+            spinbox_bind_repeat_delay_ms,
+            spinbox_bind_repeat_interval_ms,
             combobox_bind_action,
-            text_bind_action_key,
+            combobox_bind_action_key,
+            text_bind_action_key_char,
+            spinbox_bind_action_keycode,
+            checkbox_bind_action_ctrl,
+            checkbox_bind_action_shift,
+            checkbox_bind_action_alt,
+            checkbox_bind_action_meta,
+            checkbox_bind_sustain,
+            checkbox_bind_toggle,
+            checkbox_bind_feedback,
             combobox_bind_action_mousebutton,
             spinbox_bind_action_xpixels,
             spinbox_bind_action_ypixels,
@@ -303,11 +1180,328 @@ fn main() -> anyhow::Result<()> {
             spinbox_bind_action_ypos,
             combobox_bind_action_scrolldirection,
             spinbox_bind_action_scrollamount,
+            spinbox_bind_action_motion_interval_ms,
+            spinbox_bind_action_motion_acceleration,
+            checkbox_bind_action_motion_velocity,
             text_bind_action_text,
-            config_file_path
+            spinbox_bind_action_text_char_delay_ms,
+            text_bind_action_macro_steps,
+            spinbox_bind_action_macro_repeat,
+            checkbox_bind_action_macro_cancel,
+            text_bind_action_command_program,
+            text_bind_action_command_args,
+            text_bind_action_command_dir,
+            text_bind_action_command_env,
+            combobox_bind_action_command_release,
+            checkbox_bind_action_command_allowed,
+            text_bind_action_midi_port,
+            combobox_bind_action_midi_message,
+            spinbox_bind_action_midi_channel,
+            spinbox_bind_action_midi_number,
+            spinbox_bind_action_midi_value,
+            text_bind_action_midi_bytes,
+            text_bind_action_osc_host,
+            spinbox_bind_action_osc_port,
+            text_bind_action_osc_address,
+            text_bind_action_osc_args,
+            combobox_bind_action_http_method,
+            text_bind_action_http_url,
+            text_bind_action_http_headers,
+            text_bind_action_http_body,
+            spinbox_bind_action_http_timeout_ms,
+            text_bind_action_clipboard_text,
+            checkbox_bind_action_clipboard_paste,
+            checkbox_bind_action_clipboard_restore,
+            spinbox_bind_action_drag_xstart,
+            spinbox_bind_action_drag_ystart,
+            checkbox_bind_action_clickat_return,
+            spinbox_bind_action_glide_duration_ms,
+            combobox_bind_action_glide_easing,
+            combobox_bind_action_pos_unit,
+            text_bind_action_pos_monitor,
+            text_bind_action_xpercent,
+            text_bind_action_ypercent,
+            text_bind_action_drag_xstart_percent,
+            text_bind_action_drag_ystart_percent,
+            checkbox_bind_action_scroll_precise,
+            text_bind_action_cycle_actions,
+            checkbox_bind_action_cycle_wrap,
+            spinbox_bind_action_cycle_reset_ms,
+            checkbox_bind_action_cycle_reset_on_connect,
+            checkbox_bind_action_cycle_persist,
+            text_bind_action_random_actions,
+            checkbox_bind_action_random_no_repeat,
+            text_bind_release_action,
+            config_file_path,
+            allowlist_file_path
         );
 
         move |_| {
+            // Only key actions need a valid key to be picked
+            let key = if let 0 | 1 = combobox_bind_action.selected() {
+                let key = key_from_picker(
+                    combobox_bind_action_key.selected(),
+                    &text_bind_action_key_char.value(),
+                    spinbox_bind_action_keycode.value(),
+                );
+
+                match key {
+                    Ok(key) => KeyCombo {
+                        modifiers: [
+                            (&checkbox_bind_action_ctrl, Modifier::Control),
+                            (&checkbox_bind_action_shift, Modifier::Shift),
+                            (&checkbox_bind_action_alt, Modifier::Alt),
+                            (&checkbox_bind_action_meta, Modifier::Meta),
+                        ]
+                        .into_iter()
+                        .filter(|(checkbox, _)| checkbox.checked())
+                        .map(|(_, modifier)| modifier)
+                        .collect(),
+                        key,
+                    },
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &e.to_string());
+                        return;
+                    }
+                }
+            } else {
+                KeyCombo::default()
+            };
+
+            // Only commands need a valid environment
+            let env = if combobox_bind_action.selected() == 11 {
+                match parse_environment(&text_bind_action_command_env.value()) {
+                    Ok(env) => env,
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &e.to_string());
+                        return;
+                    }
+                }
+            } else {
+                BTreeMap::new()
+            };
+
+            // Only raw MIDI messages need valid bytes
+            let midi_bytes = if combobox_bind_action.selected() == 12
+                && combobox_bind_action_midi_message.selected() == 3
+            {
+                match parse_hex_bytes(&text_bind_action_midi_bytes.value()) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &e.to_string());
+                        return;
+                    }
+                }
+            } else {
+                vec![]
+            };
+
+            // Only OSC messages need valid arguments
+            let osc_args = if combobox_bind_action.selected() == 13 {
+                if !text_bind_action_osc_address.value().trim().starts_with('/') {
+                    window.modal_err("Couldn't save bind", "OSC addresses must start with `/`");
+                    return;
+                }
+
+                match parse_osc_arguments(&text_bind_action_osc_args.value()) {
+                    Ok(args) => args,
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &format!("{e:#}"));
+                        return;
+                    }
+                }
+            } else {
+                vec![]
+            };
+
+            // Only HTTP requests need a valid URL and headers
+            let http_headers = if combobox_bind_action.selected() == 14 {
+                let headers = check_url(text_bind_action_http_url.value().trim())
+                    .and_then(|()| parse_headers(&text_bind_action_http_headers.value()));
+
+                match headers {
+                    Ok(headers) => headers,
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &e.to_string());
+                        return;
+                    }
+                }
+            } else {
+                BTreeMap::new()
+            };
+
+            // Only actions moving to positions need them to be valid, as percentages are typed in
+            let (absolute_pos, drag_from) =
+                if let 5 | 16 | 17 | 19 = combobox_bind_action.selected() {
+                    let monitor = parse_monitor_choice(
+                        combobox_bind_action_pos_unit.selected(),
+                        &text_bind_action_pos_monitor.value(),
+                    );
+                    let positions = monitor.and_then(|monitor| {
+                        let position = |x: i32, y: i32, x_percent: &str, y_percent: &str| {
+                            let monitor = match &monitor {
+                                Some(monitor) => Some(MonitorPos2D {
+                                    monitor: monitor.clone(),
+                                    x_percent: parse_percent(x_percent)?,
+                                    y_percent: parse_percent(y_percent)?,
+                                }),
+                                None => None,
+                            };
+
+                            anyhow::Ok(AbsolutePos2D { x, y, monitor })
+                        };
+
+                        let to = position(
+                            spinbox_bind_action_xpos.value(),
+                            spinbox_bind_action_ypos.value(),
+                            &text_bind_action_xpercent.value(),
+                            &text_bind_action_ypercent.value(),
+                        )?;
+                        // Drags are the only action starting from a position too
+                        let from = if combobox_bind_action.selected() == 16 {
+                            position(
+                                spinbox_bind_action_drag_xstart.value(),
+                                spinbox_bind_action_drag_ystart.value(),
+                                &text_bind_action_drag_xstart_percent.value(),
+                                &text_bind_action_drag_ystart_percent.value(),
+                            )?
+                        } else {
+                            AbsolutePos2D::default()
+                        };
+
+                        anyhow::Ok((to, from))
+                    });
+
+                    match positions {
+                        Ok(positions) => positions,
+                        Err(e) => {
+                            window.modal_err("Couldn't save bind", &e.to_string());
+                            return;
+                        }
+                    }
+                } else {
+                    (AbsolutePos2D::default(), AbsolutePos2D::default())
+                };
+
+            // Only text needs valid keys
+            if combobox_bind_action.selected() == 7 {
+                if let Err(e) = parse_text(&text_bind_action_text.value()) {
+                    window.modal_err("Couldn't save bind", &e.to_string());
+                    return;
+                }
+            }
+
+            // Only macros need valid steps
+            let steps = if combobox_bind_action.selected() == 8 {
+                match parse_macro_steps(&text_bind_action_macro_steps.value()) {
+                    Ok(steps) => steps,
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &e.to_string());
+                        return;
+                    }
+                }
+            } else {
+                vec![]
+            };
+
+            // Only cycles and random actions need something valid to choose from
+            let cycle_actions = if combobox_bind_action.selected() == 20 {
+                match parse_actions(&text_bind_action_cycle_actions.value()) {
+                    Ok(actions) if actions.is_empty() => {
+                        window.modal_err(
+                            "Couldn't save bind",
+                            "there are no actions to cycle through",
+                        );
+                        return;
+                    }
+                    Ok(actions) => actions,
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &e.to_string());
+                        return;
+                    }
+                }
+            } else {
+                vec![]
+            };
+            let random_choices = if combobox_bind_action.selected() == 21 {
+                match parse_weighted_actions(&text_bind_action_random_actions.value()) {
+                    Ok(choices) if choices.is_empty() => {
+                        window.modal_err("Couldn't save bind", "there are no actions to pick from");
+                        return;
+                    }
+                    Ok(choices) => choices,
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &e.to_string());
+                        return;
+                    }
+                }
+            } else {
+                vec![]
+            };
+
+            // Written in the same way as a macro step, or left empty for none
+            let release_action = text_bind_release_action.value();
+            let release_action = if release_action.trim().is_empty() {
+                None
+            } else {
+                match BindAction::try_from(release_action.as_str()) {
+                    Ok(action) => Some(action),
+                    Err(e) => {
+                        window.modal_err("Couldn't save bind", &format!("release action: {e}"));
+                        return;
+                    }
+                }
+            };
+
+            // Shared between actions clicking, moving the mouse or scrolling once, continuously and smoothly
+            let mouse_button = match combobox_bind_action_mousebutton.selected() {
+                0 => config::MouseButton::Left,
+                1 => config::MouseButton::Right,
+                2 => config::MouseButton::Middle,
+                3 => config::MouseButton::Back,
+                4 => config::MouseButton::Forward,
+                _ => unreachable!("shouldn't be this"),
+            };
+            let relative_pos = RelativePos2D {
+                x: spinbox_bind_action_xpixels.value(),
+                y: spinbox_bind_action_ypixels.value(),
+            };
+            let scroll = ScrollBindAction {
+                direction: match combobox_bind_action_scrolldirection.selected() {
+                    0 => config::ScrollDirection::Up,
+                    1 => config::ScrollDirection::Down,
+                    2 => config::ScrollDirection::Left,
+                    3 => config::ScrollDirection::Right,
+                    _ => unreachable!("shouldn't be this"),
+                },
+                amount: spinbox_bind_action_scrollamount.value(),
+                precise: checkbox_bind_action_scroll_precise.checked(),
+            };
+            let motion = ContinuousMotion {
+                interval_ms: spinbox_bind_action_motion_interval_ms
+                    .value()
+                    .try_into()
+                    .unwrap(),
+                acceleration: spinbox_bind_action_motion_acceleration
+                    .value()
+                    .try_into()
+                    .unwrap(),
+                velocity_sensitive: checkbox_bind_action_motion_velocity.checked(),
+            };
+            let glide = Glide {
+                duration_ms: spinbox_bind_action_glide_duration_ms
+                    .value()
+                    .try_into()
+                    .unwrap(),
+                easing: match combobox_bind_action_glide_easing.selected() {
+                    0 => Easing::Linear,
+                    1 => Easing::EaseIn,
+                    2 => Easing::EaseOut,
+                    3 => Easing::EaseInOut,
+                    _ => unreachable!("shouldn't be this"),
+                },
+            };
+
             // Create a bind from the data in the GUI
             let bind = Bind {
                 note: {
@@ -318,53 +1512,197 @@ fn main() -> anyhow::Result<()> {
                 },
                 action: {
                     match combobox_bind_action.selected() {
-                        0 => BindAction::PressKey(KeyboardKeyBindAction {
-                            key: text_bind_action_key.value(),
+                        0 => BindAction::PressKey(KeyboardKeyBindAction { key: key.clone() }),
+                        1 => BindAction::HoldKey(KeyboardKeyBindAction { key }),
+                        2 => BindAction::Click(mouse_button),
+                        3 => BindAction::HoldClick(mouse_button),
+                        4 => BindAction::MoveMouseRelative(relative_pos),
+                        5 => BindAction::MoveMouseAbsolute(absolute_pos),
+                        6 => BindAction::Scroll(scroll),
+                        7 => BindAction::Text(TextBindAction {
+                            text: text_bind_action_text.value(),
+                            char_delay_ms: spinbox_bind_action_text_char_delay_ms
+                                .value()
+                                .try_into()
+                                .unwrap(),
+                        }),
+                        8 => BindAction::Macro(MacroBindAction {
+                            steps,
+                            repeat: spinbox_bind_action_macro_repeat.value().try_into().unwrap(),
+                            cancel_on_release: checkbox_bind_action_macro_cancel.checked(),
                         }),
-                        1 => BindAction::HoldKey(KeyboardKeyBindAction {
-                            key: text_bind_action_key.value(),
+                        9 => BindAction::MoveMouseContinuous(ContinuousMoveBindAction {
+                            step: relative_pos,
+                            motion,
                         }),
-                        2 => BindAction::Click(match combobox_bind_action_mousebutton.selected() {
-                            0 => config::MouseButton::Left,
-                            1 => config::MouseButton::Right,
-                            2 => config::MouseButton::Middle,
-                            _ => unreachable!("shouldn't be this"),
+                        10 => BindAction::ScrollContinuous(ContinuousScrollBindAction {
+                            step: scroll,
+                            motion,
                         }),
-                        3 => BindAction::HoldClick(
-                            match combobox_bind_action_mousebutton.selected() {
-                                0 => config::MouseButton::Left,
-                                1 => config::MouseButton::Right,
-                                2 => config::MouseButton::Middle,
+                        11 => BindAction::Command(CommandBindAction {
+                            program: text_bind_action_command_program.value().trim().to_owned(),
+                            args: text_bind_action_command_args
+                                .value()
+                                .lines()
+                                .filter(|x| !x.is_empty())
+                                .map(ToOwned::to_owned)
+                                .collect(),
+                            working_dir: Some(
+                                text_bind_action_command_dir.value().trim().to_owned(),
+                            )
+                            .filter(|x| !x.is_empty()),
+                            env,
+                            on_release: match combobox_bind_action_command_release.selected() {
+                                0 => CommandRelease::Detach,
+                                1 => CommandRelease::Wait,
+                                2 => CommandRelease::Kill,
                                 _ => unreachable!("shouldn't be this"),
                             },
-                        ),
-                        4 => BindAction::MoveMouseRelative(RelativePos2D {
-                            x: spinbox_bind_action_xpixels.value(),
-                            y: spinbox_bind_action_ypixels.value(),
                         }),
-                        5 => BindAction::MoveMouseAbsolute(AbsolutePos2D {
-                            x: spinbox_bind_action_xpos.value(),
-                            y: spinbox_bind_action_ypos.value(),
+                        12 => BindAction::SendMidi({
+                            let channel: u8 = (spinbox_bind_action_midi_channel.value() - 1)
+                                .try_into()
+                                .unwrap();
+                            let number: u8 =
+                                spinbox_bind_action_midi_number.value().try_into().unwrap();
+                            let value: u8 =
+                                spinbox_bind_action_midi_value.value().try_into().unwrap();
+
+                            SendMidiBindAction {
+                                port: Some(text_bind_action_midi_port.value().trim().to_owned())
+                                    .filter(|x| !x.is_empty()),
+                                message: match combobox_bind_action_midi_message.selected() {
+                                    0 => MidiOutMessage::Note {
+                                        channel,
+                                        note: number,
+                                        velocity: value,
+                                    },
+                                    1 => MidiOutMessage::ControlChange {
+                                        channel,
+                                        controller: number,
+                                        value,
+                                    },
+                                    2 => MidiOutMessage::ProgramChange {
+                                        channel,
+                                        program: number,
+                                    },
+                                    3 => MidiOutMessage::Raw(midi_bytes),
+                                    _ => unreachable!("shouldn't be this"),
+                                },
+                            }
+                        }),
+                        13 => BindAction::SendOsc(SendOscBindAction {
+                            host: text_bind_action_osc_host.value().trim().to_owned(),
+                            port: spinbox_bind_action_osc_port.value().try_into().unwrap(),
+                            address: text_bind_action_osc_address.value().trim().to_owned(),
+                            args: osc_args,
                         }),
-                        6 => BindAction::Scroll(ScrollBindAction {
-                            direction: match combobox_bind_action_scrolldirection.selected() {
-                                0 => config::ScrollDirection::Up,
-                                1 => config::ScrollDirection::Down,
-                                2 => config::ScrollDirection::Left,
-                                3 => config::ScrollDirection::Right,
+                        14 => BindAction::Http(HttpBindAction {
+                            method: match combobox_bind_action_http_method.selected() {
+                                0 => HttpMethod::Get,
+                                1 => HttpMethod::Post,
+                                2 => HttpMethod::Put,
                                 _ => unreachable!("shouldn't be this"),
                             },
-                            amount: spinbox_bind_action_scrollamount.value(),
+                            url: text_bind_action_http_url.value().trim().to_owned(),
+                            headers: http_headers,
+                            body: text_bind_action_http_body.value(),
+                            timeout_ms: spinbox_bind_action_http_timeout_ms
+                                .value()
+                                .try_into()
+                                .unwrap(),
                         }),
-                        7 => BindAction::Text(TextBindAction {
-                            text: text_bind_action_text.value(),
+                        15 => BindAction::Clipboard(ClipboardBindAction {
+                            text: text_bind_action_clipboard_text.value(),
+                            paste: checkbox_bind_action_clipboard_paste.checked(),
+                            restore: checkbox_bind_action_clipboard_restore.checked(),
                         }),
-                        8 => BindAction::Debug,
+                        16 => BindAction::Drag(DragBindAction {
+                            button: mouse_button,
+                            from: drag_from,
+                            to: absolute_pos,
+                            glide,
+                        }),
+                        17 => BindAction::ClickAt(ClickAtBindAction {
+                            button: mouse_button,
+                            position: absolute_pos,
+                            return_cursor: checkbox_bind_action_clickat_return.checked(),
+                        }),
+                        18 => BindAction::GlideMouseRelative(GlideRelativeBindAction {
+                            offset: relative_pos,
+                            glide,
+                        }),
+                        19 => BindAction::GlideMouseAbsolute(GlideAbsoluteBindAction {
+                            position: absolute_pos,
+                            glide,
+                        }),
+                        // Saving a cycle starts it from the first action again
+                        20 => BindAction::Cycle(CycleBindAction {
+                            actions: cycle_actions,
+                            wrap: checkbox_bind_action_cycle_wrap.checked(),
+                            position: 0,
+                            persist: checkbox_bind_action_cycle_persist.checked(),
+                            reset_after_ms: spinbox_bind_action_cycle_reset_ms
+                                .value()
+                                .try_into()
+                                .unwrap(),
+                            reset_on_connect: checkbox_bind_action_cycle_reset_on_connect.checked(),
+                        }),
+                        21 => BindAction::Random(RandomBindAction {
+                            choices: random_choices,
+                            no_repeat: checkbox_bind_action_random_no_repeat.checked(),
+                        }),
+                        22 => BindAction::Debug,
                         _ => unreachable!("shouldn't be this"),
                     }
                 },
+                trigger: match combobox_bind_trigger.selected() {
+                    0 => BindTrigger::Press,
+                    1 => BindTrigger::Tap,
+                    2 => BindTrigger::DoubleTap,
+                    3 => BindTrigger::LongPress,
+                    _ => unreachable!("shouldn't be this"),
+                },
+                layer: match usize::try_from(combobox_bind_layer.selected()) {
+                    // The first entry is the base layer
+                    Ok(0) | Err(_) => None,
+                    Ok(idx) => state_interface
+                        .get_layers()
+                        .get(idx - 1)
+                        .map(|x| x.name.clone()),
+                },
+                group: Some(text_bind_group.value().trim().to_owned()).filter(|x| !x.is_empty()),
+                sustain: checkbox_bind_sustain.checked(),
+                toggle: checkbox_bind_toggle.checked(),
+                feedback: checkbox_bind_feedback.checked(),
+                debounce_ms: spinbox_bind_debounce_ms.value().try_into().unwrap(),
+                cooldown_ms: spinbox_bind_cooldown_ms.value().try_into().unwrap(),
+                max_hold_ms: spinbox_bind_max_hold_ms.value().try_into().unwrap(),
+                auto_repeat: checkbox_bind_auto_repeat.checked().then(|| AutoRepeat {
+                    delay_ms: spinbox_bind_repeat_delay_ms.value().try_into().unwrap(),
+                    interval_ms: spinbox_bind_repeat_interval_ms.value().try_into().unwrap(),
+                }),
+                release_action,
             };
 
+            // Commands only run once they've been allowed on this computer, so a shared config can't run anything unexpected
+            if let BindAction::Command(command) = &bind.action {
+                let allowed = checkbox_bind_action_command_allowed.checked();
+
+                state_interface.set_command_allowed(
+                    command.clone(),
+                    allowed,
+                    allowlist_file_path.clone(),
+                );
+
+                if !allowed {
+                    window.modal_msg(
+                        "Command not allowed",
+                        "This command won't run until it's allowed to run on this computer",
+                    );
+                }
+            }
+
             // Update the bind
             let row = state_interface.update_active_bind(bind);
 
@@ -380,46 +1718,741 @@ fn main() -> anyhow::Result<()> {
         }
     });
 
-    // Set input midi port to use when selected in GUI
+    // Set input midi port to use when selected in GUI, and show the transpose for that port
     combobox_midi_input.on_selected(&ui, {
         shadow_clone!(state_interface);
+        shadow_clone_mut!(spinbox_port_transpose);
 
         move |selection| {
             state_interface.set_midi_input_port(selection.try_into().unwrap());
+
+            let transpose = selected_midi_input_name(&state_interface, selection)
+                .and_then(|name| {
+                    state_interface
+                        .get_input_transform()
+                        .port_transpose
+                        .get(&name)
+                        .copied()
+                })
+                .unwrap_or(0);
+            spinbox_port_transpose.set_value(i32::from(transpose));
         }
     });
 
-    // Start from GUI
-    bt_start.disable();
-    bt_start.on_clicked({
-        shadow_clone!(state_interface);
+    // Show the current gesture timings
+    {
+        let gestures = state_interface.get_gesture_config();
 
-        move |button| {
-            state_interface.start_midi_connection();
-            button.disable();
+        spinbox_double_tap_ms.set_value(gestures.double_tap_ms.try_into().unwrap());
+        spinbox_long_press_ms.set_value(gestures.long_press_ms.try_into().unwrap());
+    }
+
+    // Update gesture timings when changed in the GUI
+    let update_gesture_config = {
+        shadow_clone!(
+            state_interface,
+            spinbox_double_tap_ms,
+            spinbox_long_press_ms,
+            config_file_path
+        );
+
+        move |_| {
+            state_interface.set_gesture_config(GestureConfig {
+                double_tap_ms: spinbox_double_tap_ms.value().try_into().unwrap(),
+                long_press_ms: spinbox_long_press_ms.value().try_into().unwrap(),
+            });
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    };
+    spinbox_double_tap_ms.on_changed(update_gesture_config.clone());
+    spinbox_long_press_ms.on_changed(update_gesture_config);
+
+    // Show the current rate limit
+    let limits = state_interface.get_limit_config();
+    spinbox_max_actions_per_second.set_value(limits.max_actions_per_second.try_into().unwrap());
+    spinbox_max_hold_ms.set_value(limits.max_hold_ms.try_into().unwrap());
+
+    // Update the limits when changed in the GUI
+    spinbox_max_actions_per_second.on_changed({
+        shadow_clone!(state_interface, config_file_path);
+
+        move |value| {
+            state_interface.set_limit_config(LimitConfig {
+                max_actions_per_second: value.try_into().unwrap(),
+                ..state_interface.get_limit_config()
+            });
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
         }
     });
+    spinbox_max_hold_ms.on_changed({
+        shadow_clone!(state_interface, config_file_path);
 
-    // Stop from GUI
-    bt_stop.disable();
-    bt_stop.on_clicked({
-        shadow_clone!(state_interface);
+        move |value| {
+            state_interface.set_limit_config(LimitConfig {
+                max_hold_ms: value.try_into().unwrap(),
+                ..state_interface.get_limit_config()
+            });
 
-        move |button| {
-            state_interface.stop_midi_connection();
-            button.disable();
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
         }
     });
 
-    let mut window = Window::new(&ui, "bindmidi", 600, 400, WindowType::NoMenubar);
+    // Fill in the panic note
+    let panic_note = state_interface.get_panic_note();
+    checkbox_panic_note.set_checked(panic_note.is_some());
+    let panic_note = panic_note.unwrap_or_default();
+    combobox_panic_note.set_selected(i32::from(panic_note.get_pitch_class_offset()));
+    spinbox_panic_octave.set_value(i32::from(panic_note.get_octave()));
 
-    window.set_child(layout);
-    window.show();
+    // Update the panic note when any part of it is changed in the GUI
+    let update_panic_note = {
+        shadow_clone!(
+            state_interface,
+            checkbox_panic_note,
+            combobox_panic_note,
+            spinbox_panic_octave,
+            config_file_path
+        );
 
-    let mut event_loop = ui.event_loop();
-    event_loop.on_tick({
+        move || {
+            let note = checkbox_panic_note.checked().then(|| {
+                let pitch_class_offset: u8 = combobox_panic_note.selected().try_into().unwrap();
+                let octave: i8 = spinbox_panic_octave.value().try_into().unwrap();
+
+                Note::new(pitch_class_offset, octave)
+            });
+
+            state_interface.set_panic_note(note);
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    };
+    checkbox_panic_note.on_toggled(&ui, {
+        shadow_clone!(update_panic_note);
+        move |_| update_panic_note()
+    });
+    combobox_panic_note.on_selected(&ui, {
+        shadow_clone!(update_panic_note);
+        move |_| update_panic_note()
+    });
+    spinbox_panic_octave.on_changed(move |_| update_panic_note());
+
+    // Fill in the controller feedback config, offering every MIDI output along with the one already chosen
+    let feedback = state_interface.get_feedback_config();
+    let mut feedback_output_names = state_interface.get_midi_output_names().unwrap_or_default();
+    if let Some(port) = &feedback.port {
+        if !feedback_output_names.contains(port) {
+            feedback_output_names.push(port.clone());
+        }
+    }
+
+    combobox_feedback_output.append("None");
+    for name in &feedback_output_names {
+        combobox_feedback_output.append(name);
+    }
+    combobox_feedback_output.set_selected(
+        feedback
+            .port
+            .as_ref()
+            .and_then(|port| feedback_output_names.iter().position(|x| x == port))
+            .map_or(0, |x| (x + 1).try_into().unwrap()),
+    );
+    spinbox_feedback_channel.set_value(i32::from(feedback.channel) + 1);
+    spinbox_feedback_lit_velocity.set_value(i32::from(feedback.lit_velocity));
+    spinbox_feedback_unlit_velocity.set_value(i32::from(feedback.unlit_velocity));
+
+    // Update the controller feedback config when any part of it is changed in the GUI
+    let update_feedback_config = {
+        shadow_clone!(
+            state_interface,
+            combobox_feedback_output,
+            spinbox_feedback_channel,
+            spinbox_feedback_lit_velocity,
+            spinbox_feedback_unlit_velocity,
+            config_file_path
+        );
+
+        move || {
+            state_interface.set_feedback_config(FeedbackConfig {
+                // The first entry is no output
+                port: usize::try_from(combobox_feedback_output.selected())
+                    .ok()
+                    .and_then(|x| x.checked_sub(1))
+                    .and_then(|x| feedback_output_names.get(x).cloned()),
+                channel: (spinbox_feedback_channel.value() - 1).try_into().unwrap(),
+                lit_velocity: spinbox_feedback_lit_velocity.value().try_into().unwrap(),
+                unlit_velocity: spinbox_feedback_unlit_velocity.value().try_into().unwrap(),
+            });
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    };
+    combobox_feedback_output.on_selected(&ui, {
+        shadow_clone!(update_feedback_config);
+        move |_| update_feedback_config()
+    });
+    for spinbox in [
+        &mut spinbox_feedback_channel,
+        &mut spinbox_feedback_lit_velocity,
+        &mut spinbox_feedback_unlit_velocity,
+    ] {
+        spinbox.on_changed({
+            shadow_clone!(update_feedback_config);
+            move |_| update_feedback_config()
+        });
+    }
+
+    // Show/hide velocity curve config based on the selected curve
+    let mut clean_velocity_curve_config = {
+        shadow_clone_mut!(spinbox_fixed_velocity, text_velocity_points);
+
+        move |selected| {
+            show_control_only_when!(selected,
+                spinbox_fixed_velocity: 1,
+                text_velocity_points: 4
+            );
+        }
+    };
+    combobox_velocity_curve.on_selected(&ui, clean_velocity_curve_config.clone());
+
+    // Show the current input transform
+    {
+        let transform = state_interface.get_input_transform();
+
+        spinbox_transpose.set_value(i32::from(transform.transpose));
+        spinbox_octave_shift.set_value(i32::from(transform.octave_shift));
+        text_note_map.set_value(&format_number_pairs(transform.note_map.into_iter()));
+        // Channels are shown numbered from 1, as on most MIDI devices
+        text_channel_map.set_value(&format_number_pairs(
+            transform
+                .channel_map
+                .into_iter()
+                .map(|(from, to)| (from + 1, to + 1)),
+        ));
+        combobox_velocity_curve.set_selected(i32::from(transform.velocity_curve.index()));
+        clean_velocity_curve_config(combobox_velocity_curve.selected());
+
+        match transform.velocity_curve {
+            VelocityCurve::Fixed(vel) => spinbox_fixed_velocity.set_value(i32::from(vel)),
+            VelocityCurve::Custom(points) => {
+                text_velocity_points.set_value(&format_number_pairs(points.into_iter()));
+            }
+            _ => {}
+        }
+    }
+
+    // Update the input transform via the GUI
+    bt_update_transform.on_clicked({
+        shadow_clone!(
+            state_interface,
+            window,
+            combobox_midi_input,
+            spinbox_transpose,
+            spinbox_octave_shift,
+            spinbox_port_transpose,
+            text_note_map,
+            text_channel_map,
+            combobox_velocity_curve,
+            spinbox_fixed_velocity,
+            text_velocity_points,
+            config_file_path
+        );
+
+        move |_| {
+            // Create an input transform from the data in the GUI
+            let transform = (|| -> anyhow::Result<InputTransform> {
+                let mut port_transpose = state_interface.get_input_transform().port_transpose;
+
+                if let Some(name) =
+                    selected_midi_input_name(&state_interface, combobox_midi_input.selected())
+                {
+                    match spinbox_port_transpose.value() {
+                        0 => port_transpose.remove(&name),
+                        x => port_transpose.insert(name, x.try_into().unwrap()),
+                    };
+                }
+
+                Ok(InputTransform {
+                    transpose: spinbox_transpose.value().try_into().unwrap(),
+                    octave_shift: spinbox_octave_shift.value().try_into().unwrap(),
+                    port_transpose,
+                    note_map: parse_number_pairs(&text_note_map.value(), 0..=127)
+                        .context("invalid note remap")?
+                        .into_iter()
+                        .collect(),
+                    channel_map: parse_number_pairs(&text_channel_map.value(), 1..=16)
+                        .context("invalid channel remap")?
+                        .into_iter()
+                        .map(|(from, to)| (from - 1, to - 1))
+                        .collect(),
+                    velocity_curve: match combobox_velocity_curve.selected() {
+                        0 => VelocityCurve::Linear,
+                        1 => {
+                            VelocityCurve::Fixed(spinbox_fixed_velocity.value().try_into().unwrap())
+                        }
+                        2 => VelocityCurve::Compressed,
+                        3 => VelocityCurve::Expanded,
+                        4 => VelocityCurve::Custom(
+                            parse_number_pairs(&text_velocity_points.value(), 0..=127)
+                                .context("invalid velocity curve points")?,
+                        ),
+                        _ => unreachable!("shouldn't be this"),
+                    },
+                })
+            })();
+
+            let transform = match transform {
+                Ok(x) => x,
+                Err(e) => {
+                    window.modal_err("Couldn't save input transform", &format!("{e:#}"));
+                    return;
+                }
+            };
+
+            state_interface.set_input_transform(transform);
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    });
+
+    // Fill the lists of layers with those which are configured, keeping the selected bind's layer shown
+    let mut refresh_layer_lists = {
         shadow_clone!(state_interface);
-        shadow_clone_mut!(combobox_midi_input, bt_start, bt_stop, label_status);
+        shadow_clone_mut!(combobox_layer, combobox_bind_layer);
+
+        move || {
+            combobox_layer.clear();
+            combobox_bind_layer.clear();
+            combobox_bind_layer.append(BASE_LAYER_NAME);
+
+            for layer in state_interface.get_layers() {
+                combobox_layer.append(&layer.name);
+                combobox_bind_layer.append(&layer.name);
+            }
+
+            if let Some(bind) = state_interface.get_active_bind() {
+                combobox_bind_layer.set_selected(bind_layer_index(&state_interface, &bind));
+            }
+        }
+    };
+    (refresh_layer_lists()); // Run once at startup
+
+    // Show/hide layer activation config based on the selected activation
+    let mut clean_layer_activation_config = {
+        shadow_clone_mut!(combobox_layer_note, spinbox_layer_octave);
+
+        move |selected| {
+            show_control_only_when!(selected,
+                combobox_layer_note: 1 | 2,
+                spinbox_layer_octave: 1 | 2
+            );
+        }
+    };
+    combobox_layer_activation.on_selected(&ui, clean_layer_activation_config.clone());
+
+    // Fill the layer edit form with the layer at the given index, disabling it if there isn't one
+    let mut show_layer = {
+        shadow_clone!(state_interface);
+        shadow_clone_mut!(
+            form_edit_layer,
+            bt_update_layer,
+            bt_delete_layer,
+            text_layer_name,
+            combobox_layer_activation,
+            combobox_layer_note,
+            spinbox_layer_octave
+        );
+
+        move |idx: i32| {
+            let layer = usize::try_from(idx)
+                .ok()
+                .and_then(|idx| state_interface.get_layers().into_iter().nth(idx));
+
+            enable_control_only_when!(
+                layer.is_some(),
+                form_edit_layer,
+                bt_update_layer,
+                bt_delete_layer
+            );
+
+            if let Some(layer) = layer {
+                text_layer_name.set_value(&layer.name);
+                combobox_layer_activation.set_selected(i32::from(layer.activation.index()));
+                clean_layer_activation_config(combobox_layer_activation.selected());
+
+                if let Some(note) = layer.activation.note() {
+                    combobox_layer_note.set_selected(i32::from(note.get_pitch_class_offset()));
+                    spinbox_layer_octave.set_value(i32::from(note.get_octave()));
+                }
+            }
+        }
+    };
+    (show_layer(-1)); // Run once at startup
+    combobox_layer.on_selected(&ui, show_layer.clone());
+
+    // Notify the binds table that every row may have changed, such as when a layer is renamed
+    let notify_all_binds_changed = {
+        shadow_clone!(state_interface, table_binds_model);
+
+        move || {
+            for row in 0..state_interface.len_binds() {
+                table_binds_model
+                    .borrow()
+                    .notify_row_changed(row.try_into().unwrap());
+            }
+        }
+    };
+
+    // Add new layers via the GUI
+    bt_add_layer.on_clicked({
+        shadow_clone!(state_interface, config_file_path);
+        shadow_clone_mut!(combobox_layer, refresh_layer_lists, show_layer);
+
+        move |_| {
+            // Create new layer
+            let idx: i32 = state_interface.add_default_layer().try_into().unwrap();
+
+            // Show it in the GUI
+            refresh_layer_lists();
+            combobox_layer.set_selected(idx);
+            show_layer(idx);
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    });
+
+    // Update layers via the GUI
+    bt_update_layer.on_clicked({
+        shadow_clone!(
+            state_interface,
+            window,
+            text_layer_name,
+            combobox_layer_activation,
+            combobox_layer_note,
+            spinbox_layer_octave,
+            notify_all_binds_changed,
+            config_file_path
+        );
+        shadow_clone_mut!(combobox_layer, refresh_layer_lists);
+
+        move |_| {
+            let Ok(idx) = usize::try_from(combobox_layer.selected()) else {
+                return;
+            };
+
+            // Create a layer from the data in the GUI
+            let layer = Layer {
+                name: text_layer_name.value().trim().into(),
+                activation: {
+                    let note = {
+                        let pitch_class_offset: u8 =
+                            combobox_layer_note.selected().try_into().unwrap();
+                        let octave: i8 = spinbox_layer_octave.value().try_into().unwrap();
+
+                        Note::new(pitch_class_offset, octave)
+                    };
+
+                    match combobox_layer_activation.selected() {
+                        0 => LayerActivation::WhileSustain,
+                        1 => LayerActivation::WhileNoteHeld(note),
+                        2 => LayerActivation::ToggledByNote(note),
+                        _ => unreachable!("shouldn't be this"),
+                    }
+                },
+            };
+
+            // Update the layer
+            if let Err(e) = state_interface.update_layer(idx, layer) {
+                window.modal_err("Couldn't save layer", &e.to_string());
+                return;
+            }
+
+            // Show the new name in the GUI, including for binds in the layer
+            refresh_layer_lists();
+            combobox_layer.set_selected(idx.try_into().unwrap());
+            notify_all_binds_changed();
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    });
+
+    // Delete layers via the GUI
+    bt_delete_layer.on_clicked({
+        shadow_clone!(state_interface, notify_all_binds_changed, config_file_path);
+        shadow_clone_mut!(combobox_layer, refresh_layer_lists, show_layer);
+
+        move |_| {
+            let Ok(idx) = usize::try_from(combobox_layer.selected()) else {
+                return;
+            };
+
+            // Delete the layer, moving its binds to the base layer
+            if state_interface.delete_layer(idx).is_err() {
+                return;
+            }
+
+            // Remove it from the GUI
+            refresh_layer_lists();
+            show_layer(-1);
+            notify_all_binds_changed();
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    });
+
+    // Fill the list of MIDI thru routes with those which are configured
+    let refresh_thru_routes = {
+        shadow_clone!(state_interface);
+        shadow_clone_mut!(combobox_thru_route);
+
+        move || {
+            combobox_thru_route.clear();
+
+            for route in state_interface.get_thru_routes() {
+                combobox_thru_route.append(&thru_route_name(&route));
+            }
+        }
+    };
+    (refresh_thru_routes()); // Run once at startup
+
+    // Fill the thru route edit form with the route at the given index, disabling it if there isn't one
+    let mut show_thru_route = {
+        shadow_clone!(state_interface);
+        shadow_clone_mut!(
+            form_edit_thru,
+            bt_update_thru_route,
+            bt_delete_thru_route,
+            text_thru_input,
+            text_thru_output,
+            text_thru_channels,
+            checkbox_thru_notes,
+            checkbox_thru_control_changes,
+            checkbox_thru_program_changes,
+            checkbox_thru_pitch_bend,
+            checkbox_thru_aftertouch,
+            checkbox_thru_system,
+            spinbox_thru_lowest_note,
+            spinbox_thru_highest_note,
+            spinbox_thru_transpose,
+            spinbox_thru_output_channel,
+            checkbox_thru_skip_bound_notes
+        );
+
+        move |idx: i32| {
+            let route = usize::try_from(idx)
+                .ok()
+                .and_then(|idx| state_interface.get_thru_routes().into_iter().nth(idx));
+
+            enable_control_only_when!(
+                route.is_some(),
+                form_edit_thru,
+                bt_update_thru_route,
+                bt_delete_thru_route
+            );
+
+            if let Some(route) = route {
+                text_thru_input.set_value(route.input.as_deref().unwrap_or_default());
+                text_thru_output.set_value(route.output.as_deref().unwrap_or_default());
+                // Channels are shown numbered from 1, as on most MIDI devices
+                text_thru_channels.set_value(
+                    &route
+                        .channels
+                        .iter()
+                        .map(|x| (x + 1).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                );
+                checkbox_thru_notes.set_checked(route.messages.notes);
+                checkbox_thru_control_changes.set_checked(route.messages.control_changes);
+                checkbox_thru_program_changes.set_checked(route.messages.program_changes);
+                checkbox_thru_pitch_bend.set_checked(route.messages.pitch_bend);
+                checkbox_thru_aftertouch.set_checked(route.messages.aftertouch);
+                checkbox_thru_system.set_checked(route.messages.system);
+                spinbox_thru_lowest_note.set_value(i32::from(route.lowest_note));
+                spinbox_thru_highest_note.set_value(i32::from(route.highest_note));
+                spinbox_thru_transpose.set_value(i32::from(route.transpose));
+                spinbox_thru_output_channel
+                    .set_value(route.output_channel.map_or(0, |x| i32::from(x) + 1));
+                checkbox_thru_skip_bound_notes.set_checked(route.skip_bound_notes);
+            }
+        }
+    };
+    (show_thru_route(-1)); // Run once at startup
+    combobox_thru_route.on_selected(&ui, show_thru_route.clone());
+
+    // Add new thru routes via the GUI
+    bt_add_thru_route.on_clicked({
+        shadow_clone!(state_interface, config_file_path);
+        shadow_clone_mut!(combobox_thru_route, refresh_thru_routes, show_thru_route);
+
+        move |_| {
+            // Create new route
+            let idx: i32 = state_interface.add_default_thru_route().try_into().unwrap();
+
+            // Show it in the GUI
+            refresh_thru_routes();
+            combobox_thru_route.set_selected(idx);
+            show_thru_route(idx);
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    });
+
+    // Update thru routes via the GUI
+    bt_update_thru_route.on_clicked({
+        shadow_clone!(
+            state_interface,
+            window,
+            text_thru_input,
+            text_thru_output,
+            text_thru_channels,
+            checkbox_thru_notes,
+            checkbox_thru_control_changes,
+            checkbox_thru_program_changes,
+            checkbox_thru_pitch_bend,
+            checkbox_thru_aftertouch,
+            checkbox_thru_system,
+            spinbox_thru_lowest_note,
+            spinbox_thru_highest_note,
+            spinbox_thru_transpose,
+            spinbox_thru_output_channel,
+            checkbox_thru_skip_bound_notes,
+            config_file_path
+        );
+        shadow_clone_mut!(combobox_thru_route, refresh_thru_routes);
+
+        move |_| {
+            let Ok(idx) = usize::try_from(combobox_thru_route.selected()) else {
+                return;
+            };
+
+            let channels = match parse_channels(&text_thru_channels.value()) {
+                Ok(x) => x,
+                Err(e) => {
+                    window.modal_err("Couldn't save route", &format!("{e:#}"));
+                    return;
+                }
+            };
+
+            // Create a route from the data in the GUI
+            let route = ThruRoute {
+                input: Some(text_thru_input.value().trim().to_owned()).filter(|x| !x.is_empty()),
+                output: Some(text_thru_output.value().trim().to_owned()).filter(|x| !x.is_empty()),
+                channels,
+                messages: ThruMessages {
+                    notes: checkbox_thru_notes.checked(),
+                    control_changes: checkbox_thru_control_changes.checked(),
+                    program_changes: checkbox_thru_program_changes.checked(),
+                    pitch_bend: checkbox_thru_pitch_bend.checked(),
+                    aftertouch: checkbox_thru_aftertouch.checked(),
+                    system: checkbox_thru_system.checked(),
+                },
+                lowest_note: spinbox_thru_lowest_note.value().try_into().unwrap(),
+                highest_note: spinbox_thru_highest_note.value().try_into().unwrap(),
+                transpose: spinbox_thru_transpose.value().try_into().unwrap(),
+                // The first value keeps the channel
+                output_channel: match spinbox_thru_output_channel.value() {
+                    0 => None,
+                    x => Some((x - 1).try_into().unwrap()),
+                },
+                skip_bound_notes: checkbox_thru_skip_bound_notes.checked(),
+            };
+
+            // Update the route
+            if let Err(e) = state_interface.update_thru_route(idx, route) {
+                window.modal_err("Couldn't save route", &e.to_string());
+                return;
+            }
+
+            // Show the new name in the GUI
+            refresh_thru_routes();
+            combobox_thru_route.set_selected(idx.try_into().unwrap());
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    });
+
+    // Delete thru routes via the GUI
+    bt_delete_thru_route.on_clicked({
+        shadow_clone!(state_interface, config_file_path);
+        shadow_clone_mut!(combobox_thru_route, refresh_thru_routes, show_thru_route);
+
+        move |_| {
+            let Ok(idx) = usize::try_from(combobox_thru_route.selected()) else {
+                return;
+            };
+
+            if state_interface.delete_thru_route(idx).is_err() {
+                return;
+            }
+
+            // Remove it from the GUI
+            refresh_thru_routes();
+            show_thru_route(-1);
+
+            // Save config to disk
+            state_interface.save_config(config_file_path.clone());
+        }
+    });
+
+    // Start from GUI
+    bt_start.disable();
+    bt_start.on_clicked({
+        shadow_clone!(state_interface);
+
+        move |button| {
+            state_interface.start_midi_connection();
+            button.disable();
+        }
+    });
+
+    // Stop from GUI
+    bt_stop.disable();
+    bt_stop.on_clicked({
+        shadow_clone!(state_interface);
+
+        move |button| {
+            state_interface.stop_midi_connection();
+            button.disable();
+        }
+    });
+
+    bt_panic.on_clicked({
+        shadow_clone!(state_interface);
+
+        move |_| state_interface.panic()
+    });
+
+    window.set_child(layout);
+    window.show();
+
+    let mut event_loop = ui.event_loop();
+    event_loop.on_tick({
+        shadow_clone!(state_interface);
+        shadow_clone!(table_binds_model);
+        shadow_clone_mut!(
+            combobox_midi_input,
+            bt_start,
+            bt_stop,
+            label_status,
+            label_held_notes,
+            label_held_inputs
+        );
+
+        let mut latched_binds: Vec<usize> = vec![];
 
         move || {
             // Fill combobox_midi_input with available Midi inputs
@@ -448,11 +2481,313 @@ fn main() -> anyhow::Result<()> {
             } else {
                 "Status: Not Running"
             });
+
+            // Show what's being held down, and which notes are holding each key and button
+
+            let held_notes = state_interface
+                .get_held_notes()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            label_held_notes.set_text(&none_if_empty(&held_notes.join(", ")));
+
+            let held_inputs = state_interface
+                .get_held_inputs()
+                .iter()
+                .map(|x| {
+                    let notes = x.notes.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+                    format!("{} ({})", x.input, notes.join(", "))
+                })
+                .collect::<Vec<_>>();
+            label_held_inputs.set_text(&none_if_empty(&held_inputs.join("\n")));
+
+            // Refresh the rows of toggle binds which have been latched or unlatched since last time
+
+            let mut now_latched = state_interface.get_latched_binds();
+            now_latched.sort_unstable();
+
+            if now_latched != latched_binds {
+                let len_binds = state_interface.len_binds();
+
+                for row in latched_binds.iter().chain(&now_latched) {
+                    // Deleted binds' rows are already gone
+                    if *row < len_binds {
+                        table_binds_model
+                            .borrow()
+                            .notify_row_changed((*row).try_into().unwrap());
+                    }
+                }
+
+                latched_binds = now_latched;
+            }
         }
     });
     event_loop.run_delay(500);
 
+    // Persisted cycles keep where they're up to in the config as they move on, which is saved once more before closing
+    state_interface.save_config(config_file_path);
     state_interface.shutdown();
 
     Ok(())
 }
+
+/// Returns the position of a bind's layer in the GUI's list of layers, where the base layer is first
+fn bind_layer_index(state_interface: &StateInterface, bind: &Bind) -> i32 {
+    let position = state_interface
+        .get_layers()
+        .iter()
+        .position(|x| Some(&x.name) == bind.layer.as_ref())
+        .map_or(0, |x| x + 1);
+
+    position.try_into().unwrap()
+}
+
+/// Returns the names of the keys offered by the GUI's key picker, in order
+fn key_picker_names() -> Vec<String> {
+    ["Character", "Raw Keycode"]
+        .into_iter()
+        .map(String::from)
+        .chain(NamedKey::ALL.iter().map(|x| x.name().into()))
+        .chain((1..=MAX_FUNCTION_KEY).map(|x| format!("F{x}")))
+        .collect()
+}
+
+/// Returns the position of a key in the GUI's key picker
+fn key_picker_index(key: &Key) -> i32 {
+    let position = match key {
+        Key::Char(_) => 0,
+        Key::Raw(_) => 1,
+        Key::Named(x) => {
+            2 + NamedKey::ALL
+                .iter()
+                .position(|y| y == x)
+                .expect("every named key is in the list")
+        }
+        Key::Function(x) => 1 + NamedKey::ALL.len() + usize::from(*x),
+    };
+
+    position.try_into().unwrap()
+}
+
+/// Create a key from the position picked in the GUI's key picker, along with the character and keycode entered for it
+fn key_from_picker(idx: i32, character: &str, keycode: i32) -> anyhow::Result<Key> {
+    let idx = usize::try_from(idx).map_err(|_| anyhow!("no key has been picked"))?;
+
+    Ok(match idx {
+        0 => {
+            let mut chars = character.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(x), None) => Key::Char(x),
+                _ => bail!("the character for the key must be exactly one character"),
+            }
+        }
+        1 => Key::Raw(keycode.try_into()?),
+        x if x < 2 + NamedKey::ALL.len() => Key::Named(NamedKey::ALL[x - 2]),
+        x => Key::Function((x - 1 - NamedKey::ALL.len()).try_into()?),
+    })
+}
+
+/// Returns the given text, or "None" if it's empty
+/// Show actions written as steps in an entry, warning if any of them can't be written, as saving would lose them
+fn show_steps(window: &Window, entry: &mut impl TextEntry, steps: Option<String>) {
+    let Some(steps) = steps else {
+        entry.set_value("");
+        window.modal_err(
+            "Couldn't show bind",
+            "Some of its actions can't be written as steps, such as commands or glides, so none are shown here, and saving the bind will remove them",
+        );
+        return;
+    };
+
+    entry.set_value(&steps);
+}
+
+fn none_if_empty(text: &str) -> String {
+    if text.is_empty() {
+        "None".into()
+    } else {
+        text.into()
+    }
+}
+
+/// Parse bytes written in hexadecimal like `F0 7E 7F 06 01 F7`
+fn parse_hex_bytes(text: &str) -> anyhow::Result<Vec<u8>> {
+    let bytes = text
+        .split_whitespace()
+        .map(|x| {
+            u8::from_str_radix(x.trim_start_matches("0x"), 16)
+                .map_err(|_| anyhow!("`{x}` isn't a byte written in hexadecimal"))
+        })
+        .collect::<anyhow::Result<Vec<u8>>>()?;
+
+    if bytes.is_empty() {
+        bail!("there must be at least one byte to send");
+    }
+
+    Ok(bytes)
+}
+
+/// Write bytes in hexadecimal, the opposite of [`parse_hex_bytes`]
+fn format_hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|x| format!("{x:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse environment variables written one per line like `NAME=value`. Blank lines are skipped
+fn parse_environment(text: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    text.lines()
+        .filter(|x| !x.trim().is_empty())
+        .map(|x| match x.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_owned(), value.to_owned()))
+            }
+            _ => bail!("`{x}` isn't an environment variable written like `NAME=value`"),
+        })
+        .collect()
+}
+
+/// Write environment variables one per line, the opposite of [`parse_environment`]
+fn format_environment(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parse HTTP headers written one per line like `Name: value`. Blank lines are skipped
+fn parse_headers(text: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    text.lines()
+        .filter(|x| !x.trim().is_empty())
+        .map(|x| match x.split_once(':') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_owned(), value.trim().to_owned()))
+            }
+            _ => bail!("`{x}` isn't a header written like `Name: value`"),
+        })
+        .collect()
+}
+
+/// Parse which monitor positions are on, given the unit picked and the monitor written, or `None` if they're in pixels
+fn parse_monitor_choice(unit: i32, monitor: &str) -> anyhow::Result<Option<MonitorChoice>> {
+    let monitor = monitor.trim();
+
+    Ok(match unit {
+        0 => None,
+        1 => Some(MonitorChoice::Cursor),
+        2 => Some(MonitorChoice::Primary),
+        3 => Some(MonitorChoice::Numbered(monitor.parse().map_err(|_| {
+            anyhow!("`{monitor}` isn't a monitor number, counting from 1")
+        })?)),
+        4 if monitor.is_empty() => bail!("the monitor's name is needed"),
+        4 => Some(MonitorChoice::Named(monitor.into())),
+        _ => unreachable!("shouldn't be this"),
+    })
+}
+
+/// Parse a percentage of a monitor's size, written with or without a `%`
+fn parse_percent(text: &str) -> anyhow::Result<f64> {
+    let text = text.trim();
+
+    match text.trim_end_matches('%').trim().parse() {
+        Ok(x) if (0.0..=100.0).contains(&x) => Ok(x),
+        _ => bail!("`{text}` isn't a percentage from 0 to 100"),
+    }
+}
+
+/// Write the percentage across, or down, a monitor a position is, the opposite of [`parse_percent`]. Positions in
+/// pixels are left empty
+fn format_percent(position: &AbsolutePos2D, across: bool) -> String {
+    position
+        .monitor
+        .as_ref()
+        .map(|x| if across { x.x_percent } else { x.y_percent })
+        .map(|x| x.to_string())
+        .unwrap_or_default()
+}
+
+/// Write HTTP headers one per line, the opposite of [`parse_headers`]
+fn format_headers(headers: &BTreeMap<String, String>) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the name of the MIDI input at the given position in the GUI's list of inputs, if there is one
+fn selected_midi_input_name(state_interface: &StateInterface, idx: i32) -> Option<String> {
+    let idx = usize::try_from(idx).ok()?;
+
+    state_interface
+        .get_midi_input_names()
+        .ok()?
+        .into_iter()
+        .nth(idx)
+}
+
+/// Parse a list of pairs of numbers written like `36=60, 38=62`, each of which must be in the given range
+fn parse_number_pairs(text: &str, range: RangeInclusive<u8>) -> anyhow::Result<Vec<(u8, u8)>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|pair| {
+            let (from, to) = pair
+                .split_once('=')
+                .ok_or(anyhow!("expected `from=to` but got `{pair}`"))?;
+
+            let parse = |x: &str| -> anyhow::Result<u8> {
+                let x: u8 = x
+                    .trim()
+                    .parse()
+                    .context(format!("`{}` isn't a number", x.trim()))?;
+
+                if !range.contains(&x) {
+                    bail!("{x} isn't between {} and {}", range.start(), range.end());
+                }
+
+                Ok(x)
+            };
+
+            Ok((parse(from)?, parse(to)?))
+        })
+        .collect()
+}
+
+/// Parse a list of MIDI channels like `1, 10`, numbered from 1, returning them counting from 0
+fn parse_channels(text: &str) -> anyhow::Result<Vec<u8>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            let channel: u8 = x.parse().context(format!("`{x}` isn't a number"))?;
+
+            if !(1..=16).contains(&channel) {
+                bail!("channel {channel} isn't between 1 and 16");
+            }
+
+            Ok(channel - 1)
+        })
+        .collect()
+}
+
+/// Describe a MIDI thru route by where it forwards from and to
+fn thru_route_name(route: &ThruRoute) -> String {
+    format!(
+        "{} to {}",
+        route.input.as_deref().unwrap_or("Any Input"),
+        route.output.as_deref().unwrap_or(VIRTUAL_PORT_NAME)
+    )
+}
+
+/// Write a list of pairs of numbers like `36=60, 38=62`, the opposite of [`parse_number_pairs`]
+fn format_number_pairs(pairs: impl Iterator<Item = (u8, u8)>) -> String {
+    pairs
+        .map(|(from, to)| format!("{from}={to}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
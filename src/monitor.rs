@@ -0,0 +1,236 @@
+//! Finding where the monitors connected to the computer are, so positions can be given as percentages of one.
+//!
+//! On Linux and other Unix-like systems, this needs `xrandr` to be installed, and on Windows it uses PowerShell.
+//! Otherwise, or if they can't be run, only the main monitor is known about.
+
+use std::sync::{Arc, Mutex};
+
+#[cfg(any(all(unix, not(target_os = "macos")), windows))]
+use std::process::{Command, Stdio};
+
+use anyhow::bail;
+
+use crate::config::{AbsolutePos2D, MonitorChoice, MonitorPos2D};
+
+/// Where a monitor is on the screen, in pixels
+struct Monitor {
+    name: String,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    /// Is this the monitor the system treats as the main one?
+    primary: bool,
+}
+
+impl Monitor {
+    /// Is the given position on this monitor?
+    fn contains(&self, (x, y): (i32, i32)) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// Returns the monitors connected, in the order the system lists them, given the size of the main monitor in case
+/// they can't be listed
+fn monitors(main_size: (i32, i32)) -> Vec<Monitor> {
+    match list_monitors() {
+        Ok(monitors) if !monitors.is_empty() => monitors,
+        _ => vec![Monitor {
+            name: "Main".into(),
+            x: 0,
+            y: 0,
+            width: main_size.0,
+            height: main_size.1,
+            primary: true,
+        }],
+    }
+}
+
+/// The monitors connected, listed once and then kept, as listing them means running another program which can be slow.
+///
+/// Clones share the same list, as every executor moves the mouse around the same monitors.
+#[derive(Clone, Default)]
+pub struct Monitors {
+    listed: Arc<Mutex<Option<Vec<Monitor>>>>,
+}
+
+impl Monitors {
+    /// List the monitors again now, given the size of the main monitor, in case they've changed
+    pub fn refresh(&self, main_size: (i32, i32)) {
+        *self.listed.lock().unwrap() = Some(monitors(main_size));
+    }
+
+    /// Returns where a position is on the screen in pixels, given where the cursor is and the size of the main monitor.
+    ///
+    /// If a named or numbered monitor can't be found, the monitors are listed again before giving up
+    pub fn locate(
+        &self,
+        position: &AbsolutePos2D,
+        cursor: (i32, i32),
+        main_size: (i32, i32),
+    ) -> anyhow::Result<(i32, i32)> {
+        let Some(MonitorPos2D {
+            monitor: choice,
+            x_percent,
+            y_percent,
+        }) = &position.monitor
+        else {
+            return Ok((position.x, position.y));
+        };
+
+        let mut listed = self.listed.lock().unwrap();
+        let mut list = listed.get_or_insert_with(|| monitors(main_size));
+
+        // A named or numbered monitor may have been plugged in since they were listed
+        let relist = matches!(choice, MonitorChoice::Named(_) | MonitorChoice::Numbered(_));
+        if relist && find(list, choice, cursor).is_none() {
+            list = listed.insert(monitors(main_size));
+        }
+
+        // Anywhere off the monitors is taken to be on the main one
+        let monitor = match choice {
+            MonitorChoice::Cursor => find(list, choice, cursor).or_else(|| primary(list)),
+            _ => find(list, choice, cursor),
+        };
+        let Some(monitor) = monitor else {
+            let names: Vec<&str> = list.iter().map(|x| x.name.as_str()).collect();
+            bail!(
+                "couldn't find {}, as the monitors are {}",
+                describe_monitor(choice),
+                names.join(", ")
+            );
+        };
+
+        // Kept on the monitor, as the far edges are just off it
+        let along = |start: i32, size: i32, percent: f64| -> i32 {
+            let offset = (f64::from(size) * percent.clamp(0.0, 100.0) / 100.0).round();
+
+            // Always small enough to fit, as it's no bigger than the monitor
+            #[allow(clippy::cast_possible_truncation)]
+            let offset = offset as i32;
+
+            start + offset.min(size - 1).max(0)
+        };
+
+        Ok((
+            along(monitor.x, monitor.width, *x_percent),
+            along(monitor.y, monitor.height, *y_percent),
+        ))
+    }
+}
+
+/// Returns the main monitor, or the first if none are marked as the main one
+fn primary(monitors: &[Monitor]) -> Option<&Monitor> {
+    monitors.iter().find(|x| x.primary).or(monitors.first())
+}
+
+/// Returns the chosen monitor, if it's one of those given
+fn find<'a>(
+    monitors: &'a [Monitor],
+    choice: &MonitorChoice,
+    cursor: (i32, i32),
+) -> Option<&'a Monitor> {
+    match choice {
+        MonitorChoice::Cursor => monitors.iter().find(|x| x.contains(cursor)),
+        MonitorChoice::Primary => primary(monitors),
+        MonitorChoice::Numbered(number) => number.checked_sub(1).and_then(|x| monitors.get(x)),
+        MonitorChoice::Named(name) => monitors.iter().find(|x| x.name.eq_ignore_ascii_case(name)),
+    }
+}
+
+/// Describes which monitor is chosen, for use in messages
+fn describe_monitor(choice: &MonitorChoice) -> String {
+    match choice {
+        MonitorChoice::Cursor => "the monitor the cursor is on".into(),
+        MonitorChoice::Primary => "the main monitor".into(),
+        MonitorChoice::Numbered(x) => format!("monitor {x}"),
+        MonitorChoice::Named(x) => format!("a monitor named `{x}`"),
+    }
+}
+
+/// Run a program listing the monitors, returning what it printed
+#[cfg(any(all(unix, not(target_os = "macos")), windows))]
+fn run(program: &str, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| anyhow::anyhow!("couldn't run `{program}`: {e}"))?;
+
+    if !output.status.success() {
+        bail!("`{program}` couldn't list the monitors");
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Lists the monitors using `xrandr`, which prints them like `0: +*DP-1 2560/597x1440/336+0+0  DP-1`
+#[cfg(all(unix, not(target_os = "macos")))]
+fn list_monitors() -> anyhow::Result<Vec<Monitor>> {
+    let output = run("xrandr", &["--listmonitors"])?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let [_, flags, geometry, name] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+                return None;
+            };
+
+            // Sizes are followed by the physical size in millimetres, which isn't needed
+            let (width, rest) = geometry.split_once('x')?;
+            let mut rest = rest.split('+');
+            let height = rest.next()?;
+            let pixels = |x: &str| x.split('/').next()?.parse().ok();
+
+            Some(Monitor {
+                name: name.to_owned(),
+                x: rest.next()?.parse().ok()?,
+                y: rest.next()?.parse().ok()?,
+                width: pixels(width)?,
+                height: pixels(height)?,
+                primary: flags.contains('*'),
+            })
+        })
+        .collect())
+}
+
+/// Lists the monitors using PowerShell, printing each as its position, size, whether it's primary, then its name
+#[cfg(windows)]
+fn list_monitors() -> anyhow::Result<Vec<Monitor>> {
+    let output = run(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Windows.Forms; [System.Windows.Forms.Screen]::AllScreens | ForEach-Object { '{0} {1} {2} {3} {4} {5}' -f $_.Bounds.X, $_.Bounds.Y, $_.Bounds.Width, $_.Bounds.Height, $_.Primary, $_.DeviceName }",
+        ],
+    )?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let [x, y, width, height, primary, name] =
+                line.trim().splitn(6, ' ').collect::<Vec<_>>()[..]
+            else {
+                return None;
+            };
+
+            Some(Monitor {
+                name: name.to_owned(),
+                x: x.parse().ok()?,
+                y: y.parse().ok()?,
+                width: width.parse().ok()?,
+                height: height.parse().ok()?,
+                primary: primary.eq_ignore_ascii_case("true"),
+            })
+        })
+        .collect())
+}
+
+/// There's nothing built in to list the monitors with, so only the main monitor is known about
+#[cfg(not(any(all(unix, not(target_os = "macos")), windows)))]
+#[allow(clippy::unnecessary_wraps)]
+fn list_monitors() -> anyhow::Result<Vec<Monitor>> {
+    Ok(vec![])
+}
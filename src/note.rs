@@ -4,7 +4,7 @@ use anyhow::bail;
 use musical_scales::{Pitch, PitchClass};
 use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Note {
     midi: u8,
@@ -34,6 +34,10 @@ impl Note {
             - 1
     }
 
+    pub fn get_midi(&self) -> u8 {
+        self.midi
+    }
+
     pub fn from_midi(midi: u8) -> Self {
         Self { midi }
     }
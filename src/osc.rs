@@ -0,0 +1,262 @@
+//! Sending of OSC messages over UDP, as used to control lighting, video and show control software.
+//!
+//! Arguments are written one per line, each being one of `int <number>`, `float <number>`, `string <text>`,
+//! `true` or `false`. Numbers and text can contain placeholders filled in from the note which sent the message,
+//! as described in [`crate::template`].
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::{
+    bind::NoteEvent,
+    config::{OscArgument, SendOscBindAction},
+    note::Note,
+    template::fill_template,
+};
+
+/// Sends OSC messages, keeping a socket to send from and where each host was found, so messages sent quickly one
+/// after another don't each look up the host and open a socket.
+///
+/// Clones share the same sockets and hosts, as every executor sends from the same place.
+#[derive(Clone, Default)]
+pub struct OscSender {
+    sockets: Arc<Mutex<Sockets>>,
+}
+
+/// What an [`OscSender`] keeps between messages
+#[derive(Default)]
+struct Sockets {
+    /// Sends to IPv4 addresses, once one has been sent to
+    v4: Option<UdpSocket>,
+    /// Sends to IPv6 addresses, once one has been sent to
+    v6: Option<UdpSocket>,
+    /// Where each host and port sent to was found
+    hosts: HashMap<(String, u16), SocketAddr>,
+}
+
+impl OscSender {
+    /// Send the message of a send OSC action, filling in its arguments from the note which sent it
+    pub fn send(&self, action: &SendOscBindAction, event: &NoteEvent) -> anyhow::Result<()> {
+        let packet = encode_message(&action.address, &action.args, event)?;
+
+        let mut sockets = self.sockets.lock().unwrap();
+        let Sockets { v4, v6, hosts } = &mut *sockets;
+
+        let host = (action.host.clone(), action.port);
+        if !hosts.contains_key(&host) {
+            let target = (action.host.as_str(), action.port)
+                .to_socket_addrs()
+                .context(format!("couldn't find `{}`", action.host))?
+                .next()
+                .ok_or(anyhow!("couldn't find `{}`", action.host))?;
+
+            hosts.insert(host.clone(), target);
+        }
+        let target = hosts[&host];
+
+        // Sent from whichever port is free, on the same kind of address as the target
+        let socket = match (target.is_ipv4(), v4, v6) {
+            (true, Some(socket), _) | (false, _, Some(socket)) => socket,
+            (true, socket, _) => socket.insert(UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?),
+            (false, _, socket) => socket.insert(UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0))?),
+        };
+
+        // The host may have moved, so it's looked up again next time
+        if let Err(e) = socket.send_to(&packet, target) {
+            hosts.remove(&host);
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Encode an OSC message with the given address and arguments, filled in from the given note
+fn encode_message(
+    address: &str,
+    args: &[OscArgument],
+    event: &NoteEvent,
+) -> anyhow::Result<Vec<u8>> {
+    let mut packet = vec![];
+    let mut tags = String::from(",");
+    let mut data = vec![];
+
+    for arg in args {
+        match arg {
+            OscArgument::Int(x) => {
+                tags.push('i');
+                let x: i32 = fill_template(x, event)
+                    .trim()
+                    .parse()
+                    .context(format!("`{x}` isn't a whole number"))?;
+                data.extend(x.to_be_bytes());
+            }
+            OscArgument::Float(x) => {
+                tags.push('f');
+                let x: f32 = fill_template(x, event)
+                    .trim()
+                    .parse()
+                    .context(format!("`{x}` isn't a number"))?;
+                data.extend(x.to_be_bytes());
+            }
+            OscArgument::String(x) => {
+                tags.push('s');
+                push_string(&mut data, &fill_template(x, event));
+            }
+            OscArgument::Bool(true) => tags.push('T'),
+            OscArgument::Bool(false) => tags.push('F'),
+        }
+    }
+
+    push_string(&mut packet, address);
+    push_string(&mut packet, &tags);
+    packet.extend(data);
+
+    Ok(packet)
+}
+
+/// Add a string to an OSC packet, ended with a null and padded to a multiple of four bytes
+fn push_string(packet: &mut Vec<u8>, text: &str) {
+    packet.extend(text.as_bytes());
+    packet.push(0);
+
+    packet.resize(packet.len().next_multiple_of(4), 0);
+}
+
+impl TryFrom<&str> for OscArgument {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim_start();
+        let (word, rest) = value.split_once(' ').unwrap_or((value, ""));
+
+        let arg = match word.to_lowercase().as_str() {
+            "int" => OscArgument::Int(rest.trim().into()),
+            "float" => OscArgument::Float(rest.trim().into()),
+            // Text is taken as-is
+            "string" => OscArgument::String(rest.into()),
+            "true" => OscArgument::Bool(true),
+            "false" => OscArgument::Bool(false),
+            _ => bail!("`{word}` isn't a type of OSC argument"),
+        };
+
+        // Check numbers will make sense once they're filled in, by trying with a made up note
+        let example = NoteEvent {
+            note: Note::default(),
+            vel: 127,
+            channel: 0,
+        };
+        encode_message("/", std::slice::from_ref(&arg), &example)?;
+
+        Ok(arg)
+    }
+}
+
+impl Display for OscArgument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OscArgument::Int(x) => write!(f, "int {x}"),
+            OscArgument::Float(x) => write!(f, "float {x}"),
+            OscArgument::String(x) => write!(f, "string {x}"),
+            OscArgument::Bool(x) => write!(f, "{x}"),
+        }
+    }
+}
+
+/// Parse the arguments of an OSC message written one per line, skipping blank lines
+pub fn parse_osc_arguments(text: &str) -> anyhow::Result<Vec<OscArgument>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, x)| !x.trim().is_empty())
+        .map(|(idx, x)| OscArgument::try_from(x).map_err(|e| anyhow!("line {}: {e}", idx + 1)))
+        .collect()
+}
+
+/// Write the arguments of an OSC message one per line, the opposite of [`parse_osc_arguments`]
+pub fn format_osc_arguments(args: &[OscArgument]) -> String {
+    args.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::UdpSocket, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn sends_padded_message_with_type_tags() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let action = SendOscBindAction {
+            host: "127.0.0.1".into(),
+            port: receiver.local_addr().unwrap().port(),
+            address: "/cue".into(),
+            args: vec![
+                OscArgument::Int("{velocity}".into()),
+                OscArgument::Float("1.5".into()),
+                OscArgument::String("hello".into()),
+                OscArgument::Bool(true),
+                OscArgument::Bool(false),
+            ],
+        };
+        let event = NoteEvent {
+            note: Note::default(),
+            vel: 100,
+            channel: 0,
+        };
+
+        let sender = OscSender::default();
+        sender.send(&action, &event).unwrap();
+
+        let mut buf = [0; 256];
+        let len = receiver.recv(&mut buf).unwrap();
+
+        let mut expected = vec![];
+        expected.extend(b"/cue\0\0\0\0");
+        expected.extend(b",ifsTF\0\0");
+        expected.extend(100_i32.to_be_bytes());
+        expected.extend(1.5_f32.to_be_bytes());
+        expected.extend(b"hello\0\0\0");
+        assert_eq!(&buf[..len], &expected[..]);
+        assert_eq!(len % 4, 0);
+
+        // Sent again from the same socket
+        let first = sender
+            .sockets
+            .lock()
+            .unwrap()
+            .v4
+            .as_ref()
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        sender.send(&action, &event).unwrap();
+        let (len, from) = receiver.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], &expected[..]);
+        assert_eq!(from.port(), first.port());
+    }
+
+    #[test]
+    fn pads_strings_to_four_bytes() {
+        for (text, padded) in [("", 4), ("abc", 4), ("abcd", 8), ("abcdefg", 8)] {
+            let mut packet = vec![];
+            push_string(&mut packet, text);
+
+            assert_eq!(packet.len(), padded);
+            assert_eq!(&packet[..text.len()], text.as_bytes());
+            assert!(packet[text.len()..].iter().all(|x| *x == 0));
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hasher},
+    time::{Duration, Instant},
+};
+
+use crate::config::{CycleBindAction, RandomBindAction};
+
+/// Where a cycle bind is up to
+struct CyclePosition {
+    /// Index of the action to act as next
+    next: usize,
+    /// When the cycle last moved on, to know when it's been left long enough to reset
+    last_at: Instant,
+}
+
+/// Remembers what cycle and random binds have chosen, so cycles carry on in order and random binds can avoid repeating
+#[derive(Default)]
+pub struct Choices {
+    /// Where each cycle bind, by index, is up to
+    cycles: HashMap<usize, CyclePosition>,
+    /// The choice each random bind, by index, picked last
+    picked: HashMap<usize, usize>,
+}
+
+impl Choices {
+    /// Returns the index of the action a cycle bind at the given index should act as now, moving it on to the next
+    pub fn cycle(&mut self, idx: usize, action: &CycleBindAction) -> Option<usize> {
+        let len = action.actions.len();
+        if len == 0 {
+            return None;
+        }
+
+        let now = Instant::now();
+
+        // Cycles start from wherever was last saved in the config
+        let position = self.cycles.entry(idx).or_insert(CyclePosition {
+            next: action.position,
+            last_at: now,
+        });
+
+        if action.reset_after_ms != 0
+            && now.duration_since(position.last_at)
+                >= Duration::from_millis(action.reset_after_ms.into())
+        {
+            position.next = 0;
+        }
+
+        // The actions may have been shortened since the position was saved
+        let chosen = if position.next < len {
+            position.next
+        } else {
+            0
+        };
+
+        position.next = if chosen + 1 < len {
+            chosen + 1
+        } else if action.wrap {
+            0
+        } else {
+            chosen
+        };
+        position.last_at = now;
+
+        Some(chosen)
+    }
+
+    /// Returns the index of the action a cycle bind at the given index will act as next, if it's been triggered
+    pub fn cycle_position(&self, idx: usize) -> Option<usize> {
+        self.cycles.get(&idx).map(|x| x.next)
+    }
+
+    /// Returns the index of the choice a random bind at the given index should act as now, picked by weight
+    pub fn random(&mut self, idx: usize, action: &RandomBindAction) -> Option<usize> {
+        let last = self.picked.get(&idx).copied();
+
+        // The last pick is left out if asked, unless it's the only thing which could be picked
+        let pickable = |choice: usize| {
+            action.choices[choice].weight > 0
+                && !(action.no_repeat && Some(choice) == last && action.choices.len() > 1)
+        };
+
+        let total: u64 = (0..action.choices.len())
+            .filter(|x| pickable(*x))
+            .map(|x| u64::from(action.choices[x].weight))
+            .sum();
+        if total == 0 {
+            return None;
+        }
+
+        // Each `RandomState` hashes with different keys, which is random enough to pick an action with
+        let mut roll = RandomState::new().build_hasher().finish() % total;
+
+        let chosen = (0..action.choices.len())
+            .filter(|x| pickable(*x))
+            .find(|x| {
+                let weight = u64::from(action.choices[*x].weight);
+                if roll < weight {
+                    return true;
+                }
+
+                roll -= weight;
+                false
+            })?;
+
+        self.picked.insert(idx, chosen);
+
+        Some(chosen)
+    }
+
+    /// Forget what the bind at the given index has chosen, such as when it's been edited, so it starts afresh
+    pub fn forget(&mut self, idx: usize) {
+        self.cycles.remove(&idx);
+        self.picked.remove(&idx);
+    }
+
+    /// Forget what the bind at the given index has chosen as it's been deleted, moving later binds down to their new indexes
+    pub fn bind_deleted(&mut self, idx: usize) {
+        self.forget(idx);
+
+        let reindex = |x: usize| if x > idx { x - 1 } else { x };
+        self.cycles = std::mem::take(&mut self.cycles)
+            .into_iter()
+            .map(|(x, position)| (reindex(x), position))
+            .collect();
+        self.picked = std::mem::take(&mut self.picked)
+            .into_iter()
+            .map(|(x, picked)| (reindex(x), picked))
+            .collect();
+    }
+
+    /// Send the cycle binds at the given indexes back to their first action
+    pub fn reset_cycles(&mut self, indexes: impl IntoIterator<Item = usize>) {
+        let now = Instant::now();
+
+        for idx in indexes {
+            self.cycles.insert(
+                idx,
+                CyclePosition {
+                    next: 0,
+                    last_at: now,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    process::{Child, Command},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::{CommandBindAction, CommandRelease},
+    note::Note,
+};
+
+/// A program started by a bind, which hasn't been left to run on its own yet
+struct CommandRun {
+    /// The note which started the program
+    note: Note,
+    action: CommandBindAction,
+    process: Child,
+}
+
+/// How often running programs are checked on, to notice which have finished
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Runs the programs of command binds, keeping track of the ones which need stopping or waiting for
+#[derive(Default)]
+pub struct CommandRunner {
+    /// Programs which are still running, or haven't been checked on since they finished
+    runs: Vec<CommandRun>,
+    /// When the running programs should next be checked on, if there are any
+    next_check: Option<Instant>,
+}
+
+impl CommandRunner {
+    /// Start running a bind's program on behalf of the given note, unless it's already running and mustn't be run again until it's finished
+    pub fn start(&mut self, action: &CommandBindAction, note: &Note) -> anyhow::Result<()> {
+        self.forget_finished();
+
+        if action.on_release == CommandRelease::Wait
+            && self.runs.iter().any(|x| x.action.runs_same_as(action))
+        {
+            return Ok(());
+        }
+
+        let mut command = Command::new(&action.program);
+        command.args(&action.args).envs(&action.env);
+
+        if let Some(dir) = &action.working_dir {
+            command.current_dir(dir);
+        }
+
+        let process = command.spawn()?;
+
+        self.runs.push(CommandRun {
+            note: note.clone(),
+            action: action.clone(),
+            process,
+        });
+        self.next_check
+            .get_or_insert_with(|| Instant::now() + CHECK_INTERVAL);
+
+        Ok(())
+    }
+
+    /// Handle the note ending for the programs it started, stopping the ones which are stopped when it does
+    pub fn release(&mut self, note: &Note) -> anyhow::Result<()> {
+        self.forget_finished();
+
+        for run in self
+            .runs
+            .iter_mut()
+            .filter(|x| x.note == *note && x.action.on_release == CommandRelease::Kill)
+        {
+            run.process.kill()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns when the running programs should next be checked on, if there are any
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.next_check
+    }
+
+    /// Check on the running programs if it's time to, so finished ones are cleaned up soon after they exit rather than
+    /// when their bind is next used
+    pub fn advance(&mut self) {
+        let now = Instant::now();
+        if self.next_check.is_none_or(|x| x > now) {
+            return;
+        }
+
+        self.forget_finished();
+        self.next_check = (!self.runs.is_empty()).then_some(now + CHECK_INTERVAL);
+    }
+
+    /// Forget about programs which have finished, so they don't linger as zombie processes
+    fn forget_finished(&mut self) {
+        self.runs.retain_mut(|x| {
+            // Failing to check on a program is treated as it having finished, as there's nothing more to be done with it
+            matches!(x.process.try_wait(), Ok(None))
+        });
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    bind::NoteEvent,
+    config::{BindTrigger, GestureConfig},
+    note::Note,
+};
+
+/// How far through being played as a gesture a note is
+enum Progress {
+    /// Held down for the first time, since the given MIDI timestamp
+    Pressed { at: u64, event: NoteEvent },
+    /// Tapped once at the given MIDI timestamp, and could still become a double tap until the deadline
+    Tapped {
+        at: u64,
+        event: NoteEvent,
+        deadline: Instant,
+    },
+    /// Held down for the second time, soon enough after a tap to be a double tap
+    PressedAgain { event: NoteEvent },
+}
+
+/// Recognises taps, double taps and long presses on individual notes
+///
+/// Timings between the start and end of notes are taken from the MIDI timestamps,
+/// so delays in handling messages don't affect which gesture is recognised.
+#[derive(Default)]
+pub struct GestureDetector {
+    /// Notes which are partway through a gesture
+    notes: HashMap<Note, Progress>,
+}
+
+impl GestureDetector {
+    /// Handle a note starting, returning the gesture and starting event of any tap it rules out from being a double tap
+    pub fn press(
+        &mut self,
+        event: &NoteEvent,
+        timestamp: u64,
+        config: &GestureConfig,
+    ) -> Option<(BindTrigger, NoteEvent)> {
+        let tapped = match self.notes.remove(&event.note) {
+            Some(Progress::Tapped {
+                at,
+                event: tap_event,
+                ..
+            }) => {
+                if timestamp.saturating_sub(at) <= micros(config.double_tap_ms) {
+                    self.notes.insert(
+                        event.note.clone(),
+                        Progress::PressedAgain { event: tap_event },
+                    );
+
+                    return None;
+                }
+
+                // The window passed before we got round to it, so the earlier tap was on its own
+                Some((BindTrigger::Tap, tap_event))
+            }
+            _ => None,
+        };
+
+        self.notes.insert(
+            event.note.clone(),
+            Progress::Pressed {
+                at: timestamp,
+                event: event.clone(),
+            },
+        );
+
+        tapped
+    }
+
+    /// Handle a note ending, returning the gesture it completes and the event it started with, if any.
+    ///
+    /// If `await_double_tap` is true, a short tap isn't returned straight away but only once
+    /// it's clear no second tap is coming, from [`GestureDetector::expire`].
+    pub fn release(
+        &mut self,
+        note: &Note,
+        timestamp: u64,
+        await_double_tap: bool,
+        config: &GestureConfig,
+    ) -> Option<(BindTrigger, NoteEvent)> {
+        match self.notes.remove(note)? {
+            Progress::Pressed { at, event } => {
+                if timestamp.saturating_sub(at) >= micros(config.long_press_ms) {
+                    Some((BindTrigger::LongPress, event))
+                } else if await_double_tap {
+                    self.notes.insert(
+                        note.clone(),
+                        Progress::Tapped {
+                            at: timestamp,
+                            event,
+                            deadline: Instant::now()
+                                + Duration::from_millis(config.double_tap_ms.into()),
+                        },
+                    );
+
+                    None
+                } else {
+                    Some((BindTrigger::Tap, event))
+                }
+            }
+            Progress::PressedAgain { event } => Some((BindTrigger::DoubleTap, event)),
+            tapped @ Progress::Tapped { .. } => {
+                // A note can't end without starting, so there's nothing to do
+                self.notes.insert(note.clone(), tapped);
+
+                None
+            }
+        }
+    }
+
+    /// Returns the soonest time a tapped note will stop waiting to become a double tap, if any are waiting
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.notes
+            .values()
+            .filter_map(|x| match x {
+                Progress::Tapped { deadline, .. } => Some(*deadline),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// Finish waiting on any taps whose double-tap window has passed, returning them as single taps
+    pub fn expire(&mut self, now: Instant) -> Vec<(BindTrigger, NoteEvent)> {
+        let expired: Vec<Note> = self
+            .notes
+            .iter()
+            .filter_map(|(note, progress)| match progress {
+                Progress::Tapped { deadline, .. } if *deadline <= now => Some(note.clone()),
+                _ => None,
+            })
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|note| match self.notes.remove(&note) {
+                Some(Progress::Tapped { event, .. }) => Some((BindTrigger::Tap, event)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Forget all gestures in progress
+    pub fn clear(&mut self) {
+        self.notes.clear();
+    }
+}
+
+/// Convert milliseconds into the microseconds used by MIDI timestamps
+fn micros(ms: u32) -> u64 {
+    u64::from(ms) * 1000
+}
@@ -0,0 +1,222 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    bind::{BindExecuteState, Executor, NoteEvent},
+    config::{
+        AbsolutePos2D, BindAction, DragBindAction, Easing, Glide, GlideAbsoluteBindAction,
+        GlideRelativeBindAction, MouseButton,
+    },
+};
+
+/// How often the mouse is moved along a glide
+const GLIDE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A smooth movement of the mouse in progress
+struct GlideMotion {
+    /// The note event which started the glide
+    event: NoteEvent,
+    from: (f64, f64),
+    to: (f64, f64),
+    duration: Duration,
+    easing: Easing,
+    /// When the glide started, to know how far along it should be
+    started: Instant,
+    /// When the mouse is next due to be moved
+    next_at: Instant,
+    /// Mouse button held down for a drag, to release once the glide has finished
+    release: Option<MouseButton>,
+}
+
+impl GlideMotion {
+    /// Move the mouse to where the glide should be by now, returning whether it's finished
+    fn tick(&mut self, executor: &mut Executor, now: Instant) -> anyhow::Result<bool> {
+        let progress = if self.duration.is_zero() {
+            1.0
+        } else {
+            (now.duration_since(self.started).as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+        };
+        let eased = ease(self.easing, progress);
+
+        // Always on the screen, as it's between two positions which are
+        #[allow(clippy::cast_possible_truncation)]
+        let position = AbsolutePos2D {
+            x: (self.from.0 + (self.to.0 - self.from.0) * eased).round() as i32,
+            y: (self.from.1 + (self.to.1 - self.from.1) * eased).round() as i32,
+            monitor: None,
+        };
+        executor.execute_action(
+            &BindAction::MoveMouseAbsolute(position),
+            &self.event,
+            &BindExecuteState::Begin,
+        )?;
+
+        if progress < 1.0 {
+            self.next_at += GLIDE_INTERVAL;
+
+            // Rather than catching up all at once after falling behind, carry on from now
+            if self.next_at < now {
+                self.next_at = now + GLIDE_INTERVAL;
+            }
+
+            return Ok(false);
+        }
+
+        if let Some(button) = &self.release {
+            executor.execute_action(
+                &BindAction::HoldClick(button.clone()),
+                &self.event,
+                &BindExecuteState::Release,
+            )?;
+        }
+
+        Ok(true)
+    }
+}
+
+/// Returns how far along a glide should be, given how much of its time has passed, both from 0 to 1
+fn ease(easing: Easing, progress: f64) -> f64 {
+    match easing {
+        Easing::Linear => progress,
+        Easing::EaseIn => progress * progress,
+        Easing::EaseOut => 1.0 - (1.0 - progress) * (1.0 - progress),
+        Easing::EaseInOut => progress * progress * (3.0 - 2.0 * progress),
+    }
+}
+
+/// Performs smooth mouse movements and drags, which carry on until they've finished even if their note ends
+#[derive(Default)]
+pub struct GlideRunner {
+    /// Glides in progress
+    glides: Vec<GlideMotion>,
+}
+
+impl GlideRunner {
+    /// Start smoothly moving the mouse by an offset from where it is now
+    pub fn start_relative(
+        &mut self,
+        executor: &mut Executor,
+        action: &GlideRelativeBindAction,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        let (x, y) = executor.mouse_location();
+        let to = (
+            x.saturating_add(action.offset.x),
+            y.saturating_add(action.offset.y),
+        );
+
+        self.start(executor, to, &action.glide, None, event)
+    }
+
+    /// Start smoothly moving the mouse to a position from where it is now
+    pub fn start_absolute(
+        &mut self,
+        executor: &mut Executor,
+        action: &GlideAbsoluteBindAction,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        let Some(to) = executor.locate(&action.position) else {
+            return Ok(());
+        };
+
+        self.start(executor, to, &action.glide, None, event)
+    }
+
+    /// Start a drag, jumping to where it starts and pressing the button straight away
+    pub fn start_drag(
+        &mut self,
+        executor: &mut Executor,
+        action: &DragBindAction,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        // Both ends are found before moving, in case they're on whichever monitor the cursor is on
+        let (Some(from), Some(to)) = (executor.locate(&action.from), executor.locate(&action.to))
+        else {
+            return Ok(());
+        };
+
+        executor.execute_action(
+            &BindAction::MoveMouseAbsolute(AbsolutePos2D {
+                x: from.0,
+                y: from.1,
+                monitor: None,
+            }),
+            event,
+            &BindExecuteState::Begin,
+        )?;
+        executor.execute_action(
+            &BindAction::HoldClick(action.button.clone()),
+            event,
+            &BindExecuteState::Begin,
+        )?;
+
+        self.start(
+            executor,
+            to,
+            &action.glide,
+            Some(action.button.clone()),
+            event,
+        )
+    }
+
+    fn start(
+        &mut self,
+        executor: &mut Executor,
+        to: (i32, i32),
+        glide: &Glide,
+        release: Option<MouseButton>,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let (x, y) = executor.mouse_location();
+
+        let mut motion = GlideMotion {
+            event: event.clone(),
+            from: (f64::from(x), f64::from(y)),
+            to: (f64::from(to.0), f64::from(to.1)),
+            duration: Duration::from_millis(glide.duration_ms.into()),
+            easing: glide.easing,
+            started: now,
+            next_at: now,
+            release,
+        };
+
+        if !motion.tick(executor, now)? {
+            self.glides.push(motion);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the soonest time a glide's next movement is due, if any are in progress
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.glides.iter().map(|x| x.next_at).min()
+    }
+
+    /// Move each glide which is due to by now, forgetting those which have finished
+    pub fn advance(&mut self, executor: &mut Executor) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let mut result = Ok(());
+
+        self.glides.retain_mut(|glide| {
+            if result.is_err() || glide.next_at > now {
+                return true;
+            }
+
+            match glide.tick(executor, now) {
+                Ok(finished) => !finished,
+                Err(e) => {
+                    result = Err(e);
+                    false
+                }
+            }
+        });
+
+        result
+    }
+
+    /// Stop every glide, leaving the mouse wherever it's got to. Buttons held by drags are released along with
+    /// everything else held
+    pub fn clear(&mut self) {
+        self.glides.clear();
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+
+use crate::{
+    config::{Layer, LayerActivation},
+    note::Note,
+};
+
+/// MIDI controller number of the sustain pedal
+pub const SUSTAIN_CONTROLLER: u8 = 64;
+
+/// What is currently being played on the MIDI input, as far as binds are concerned
+#[derive(Default)]
+pub struct InputState {
+    /// Is the sustain pedal held down?
+    sustain: bool,
+    /// Notes currently held down
+    held_notes: HashSet<Note>,
+    /// Names of the layers currently switched on by their note
+    toggled_layers: HashSet<String>,
+}
+
+impl InputState {
+    /// Record a note starting
+    pub fn press(&mut self, note: &Note) {
+        self.held_notes.insert(note.clone());
+    }
+
+    /// Record a note ending
+    pub fn release(&mut self, note: &Note) {
+        self.held_notes.remove(note);
+    }
+
+    /// Returns the notes currently held down, lowest first
+    pub fn held_notes(&self) -> Vec<Note> {
+        let mut notes: Vec<Note> = self.held_notes.iter().cloned().collect();
+        notes.sort_unstable_by_key(Note::get_midi);
+
+        notes
+    }
+
+    /// Is the sustain pedal held down?
+    pub fn is_sustained(&self) -> bool {
+        self.sustain
+    }
+
+    /// Record a change in position of the sustain pedal
+    pub fn set_sustain(&mut self, value: u8) {
+        // Values of 64 and above mean the pedal is down
+        self.sustain = value >= 64;
+    }
+
+    /// Switch the given layer on if it's off, or off if it's on
+    pub fn toggle_layer(&mut self, name: &str) {
+        if !self.toggled_layers.remove(name) {
+            self.toggled_layers.insert(name.into());
+        }
+    }
+
+    /// Returns the names of the given layers which are currently active, keeping their order
+    pub fn active_layers(&self, layers: &[Layer]) -> Vec<String> {
+        layers
+            .iter()
+            .filter(|x| match &x.activation {
+                LayerActivation::WhileSustain => self.sustain,
+                LayerActivation::WhileNoteHeld(note) => self.held_notes.contains(note),
+                LayerActivation::ToggledByNote(_) => self.toggled_layers.contains(&x.name),
+            })
+            .map(|x| x.name.clone())
+            .collect()
+    }
+
+    /// Forget everything being played, as if starting from silence
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
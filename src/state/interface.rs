@@ -5,10 +5,22 @@ use std::{
 
 use oneshot;
 
-use crate::{bind::BindExecuteState, config::Bind, note::Note};
+use crate::{
+    bind::{BindExecuteState, NoteEvent},
+    config::{
+        Bind, CommandBindAction, FeedbackConfig, GestureConfig, InputTransform, Layer, LimitConfig,
+        ThruRoute,
+    },
+    held::Hold,
+    note::Note,
+};
+
+use super::thru::ThruFilter;
 
 pub struct StateInterface {
     channel: mpsc::SyncSender<StateMessage>,
+    /// Which incoming MIDI messages are worth forwarding, checked before asking the state to
+    thru_filter: ThruFilter,
 }
 
 impl StateInterface {
@@ -17,6 +29,7 @@ impl StateInterface {
 
         let interface = Self {
             channel: send_channel,
+            thru_filter: ThruFilter::default(),
         };
 
         (Arc::new(interface), recv_channel)
@@ -58,6 +71,13 @@ impl StateInterface {
         }
     }
 
+    pub fn get_bind_trigger_string(&self, bind_idx: usize) -> anyhow::Result<String> {
+        match self.request(StateMessageRequest::TriggerString(bind_idx)) {
+            StateMessageResponse::TriggerString(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
     pub fn set_active_bind(&self, bind: Option<usize>) {
         match self.request(StateMessageRequest::SetActiveBind(bind)) {
             StateMessageResponse::SetActiveBind => (),
@@ -135,13 +155,223 @@ impl StateInterface {
         }
     }
 
-    pub fn execute_binds(&self, note: Note, vel: u8, state: BindExecuteState) {
-        match self.request(StateMessageRequest::ExecuteBindsForNote(note, vel, state)) {
+    pub fn execute_binds(&self, event: NoteEvent, state: BindExecuteState, timestamp: u64) {
+        match self.request(StateMessageRequest::ExecuteBindsForNote(
+            event, state, timestamp,
+        )) {
             StateMessageResponse::ExecuteBindsForNote => (),
             _ => unimplemented!("wrong response type"),
         }
     }
 
+    pub fn get_gesture_config(&self) -> GestureConfig {
+        match self.request(StateMessageRequest::GestureConfig) {
+            StateMessageResponse::GestureConfig(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn set_gesture_config(&self, gestures: GestureConfig) {
+        match self.request(StateMessageRequest::SetGestureConfig(gestures)) {
+            StateMessageResponse::SetGestureConfig => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn control_change(&self, controller: u8, value: u8) {
+        match self.request(StateMessageRequest::ControlChange(controller, value)) {
+            StateMessageResponse::ControlChange => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_bind_layer_string(&self, bind_idx: usize) -> anyhow::Result<String> {
+        match self.request(StateMessageRequest::LayerString(bind_idx)) {
+            StateMessageResponse::LayerString(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    /// Forward an incoming MIDI message through the thru routes, unless none of them could forward it
+    pub fn forward_midi(&self, bytes: &[u8]) {
+        if !self.thru_filter.forwards(bytes) {
+            return;
+        }
+
+        match self.request(StateMessageRequest::ForwardMidi(bytes.to_vec())) {
+            StateMessageResponse::ForwardMidi => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    /// Update which incoming MIDI messages are worth forwarding, as the thru routes have changed
+    pub fn thru_routes_changed(&self, routes: &[ThruRoute]) {
+        self.thru_filter.routes_changed(routes);
+    }
+
+    pub fn get_thru_routes(&self) -> Vec<ThruRoute> {
+        match self.request(StateMessageRequest::ThruRoutes) {
+            StateMessageResponse::ThruRoutes(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn add_default_thru_route(&self) -> usize {
+        match self.request(StateMessageRequest::AddDefaultThruRoute) {
+            StateMessageResponse::AddDefaultThruRoute(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn update_thru_route(&self, idx: usize, route: ThruRoute) -> anyhow::Result<()> {
+        match self.request(StateMessageRequest::UpdateThruRoute(idx, route)) {
+            StateMessageResponse::UpdateThruRoute(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn delete_thru_route(&self, idx: usize) -> anyhow::Result<()> {
+        match self.request(StateMessageRequest::DeleteThruRoute(idx)) {
+            StateMessageResponse::DeleteThruRoute(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_layers(&self) -> Vec<Layer> {
+        match self.request(StateMessageRequest::Layers) {
+            StateMessageResponse::Layers(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn add_default_layer(&self) -> usize {
+        match self.request(StateMessageRequest::AddDefaultLayer) {
+            StateMessageResponse::AddDefaultLayer(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn update_layer(&self, idx: usize, layer: Layer) -> anyhow::Result<()> {
+        match self.request(StateMessageRequest::UpdateLayer(idx, layer)) {
+            StateMessageResponse::UpdateLayer(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn delete_layer(&self, idx: usize) -> anyhow::Result<()> {
+        match self.request(StateMessageRequest::DeleteLayer(idx)) {
+            StateMessageResponse::DeleteLayer(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_input_transform(&self) -> InputTransform {
+        match self.request(StateMessageRequest::InputTransform) {
+            StateMessageResponse::InputTransform(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn set_input_transform(&self, transform: InputTransform) {
+        match self.request(StateMessageRequest::SetInputTransform(transform)) {
+            StateMessageResponse::SetInputTransform => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_limit_config(&self) -> LimitConfig {
+        match self.request(StateMessageRequest::LimitConfig) {
+            StateMessageResponse::LimitConfig(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn set_limit_config(&self, limits: LimitConfig) {
+        match self.request(StateMessageRequest::SetLimitConfig(limits)) {
+            StateMessageResponse::SetLimitConfig => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_midi_output_names(&self) -> anyhow::Result<Vec<String>> {
+        match self.request(StateMessageRequest::MidiOutputNames) {
+            StateMessageResponse::MidiOutputNames(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_feedback_config(&self) -> FeedbackConfig {
+        match self.request(StateMessageRequest::FeedbackConfig) {
+            StateMessageResponse::FeedbackConfig(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn set_feedback_config(&self, feedback: FeedbackConfig) {
+        match self.request(StateMessageRequest::SetFeedbackConfig(feedback)) {
+            StateMessageResponse::SetFeedbackConfig => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn is_command_allowed(&self, command: CommandBindAction) -> bool {
+        match self.request(StateMessageRequest::IsCommandAllowed(command)) {
+            StateMessageResponse::IsCommandAllowed(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn set_command_allowed(&self, command: CommandBindAction, allowed: bool, path: PathBuf) {
+        match self.request(StateMessageRequest::SetCommandAllowed(
+            command, allowed, path,
+        )) {
+            StateMessageResponse::SetCommandAllowed => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn panic(&self) {
+        match self.request(StateMessageRequest::Panic) {
+            StateMessageResponse::Panic => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_panic_note(&self) -> Option<Note> {
+        match self.request(StateMessageRequest::PanicNote) {
+            StateMessageResponse::PanicNote(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn set_panic_note(&self, note: Option<Note>) {
+        match self.request(StateMessageRequest::SetPanicNote(note)) {
+            StateMessageResponse::SetPanicNote => (),
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_held_notes(&self) -> Vec<Note> {
+        match self.request(StateMessageRequest::HeldNotes) {
+            StateMessageResponse::HeldNotes(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_held_inputs(&self) -> Vec<Hold> {
+        match self.request(StateMessageRequest::HeldInputs) {
+            StateMessageResponse::HeldInputs(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
+    pub fn get_latched_binds(&self) -> Vec<usize> {
+        match self.request(StateMessageRequest::LatchedBinds) {
+            StateMessageResponse::LatchedBinds(x) => x,
+            _ => unimplemented!("wrong response type"),
+        }
+    }
+
     pub fn save_config(&self, path: PathBuf) {
         match self.request(StateMessageRequest::SaveConfig(path)) {
             StateMessageResponse::SaveConfig => (),
@@ -177,7 +407,36 @@ pub enum StateMessageRequest {
     StartMidiConnection,
     StopMidiConnection,
     HasMidiConnection,
-    ExecuteBindsForNote(Note, u8, BindExecuteState),
+    ExecuteBindsForNote(NoteEvent, BindExecuteState, u64),
+    TriggerString(usize),
+    GestureConfig,
+    SetGestureConfig(GestureConfig),
+    ControlChange(u8, u8),
+    LayerString(usize),
+    Layers,
+    AddDefaultLayer,
+    UpdateLayer(usize, Layer),
+    DeleteLayer(usize),
+    ForwardMidi(Vec<u8>),
+    ThruRoutes,
+    AddDefaultThruRoute,
+    UpdateThruRoute(usize, ThruRoute),
+    DeleteThruRoute(usize),
+    InputTransform,
+    SetInputTransform(InputTransform),
+    LimitConfig,
+    SetLimitConfig(LimitConfig),
+    MidiOutputNames,
+    FeedbackConfig,
+    SetFeedbackConfig(FeedbackConfig),
+    IsCommandAllowed(CommandBindAction),
+    SetCommandAllowed(CommandBindAction, bool, PathBuf),
+    Panic,
+    PanicNote,
+    SetPanicNote(Option<Note>),
+    HeldNotes,
+    HeldInputs,
+    LatchedBinds,
     SaveConfig(PathBuf),
     Shutdown,
 }
@@ -198,6 +457,35 @@ pub enum StateMessageResponse {
     StopMidiConnection,
     HasMidiConnection(bool),
     ExecuteBindsForNote,
+    TriggerString(anyhow::Result<String>),
+    GestureConfig(GestureConfig),
+    SetGestureConfig,
+    ControlChange,
+    LayerString(anyhow::Result<String>),
+    Layers(Vec<Layer>),
+    AddDefaultLayer(usize),
+    UpdateLayer(anyhow::Result<()>),
+    DeleteLayer(anyhow::Result<()>),
+    ForwardMidi,
+    ThruRoutes(Vec<ThruRoute>),
+    AddDefaultThruRoute(usize),
+    UpdateThruRoute(anyhow::Result<()>),
+    DeleteThruRoute(anyhow::Result<()>),
+    InputTransform(InputTransform),
+    SetInputTransform,
+    LimitConfig(LimitConfig),
+    SetLimitConfig,
+    MidiOutputNames(anyhow::Result<Vec<String>>),
+    FeedbackConfig(FeedbackConfig),
+    SetFeedbackConfig,
+    IsCommandAllowed(bool),
+    SetCommandAllowed,
+    Panic,
+    PanicNote(Option<Note>),
+    SetPanicNote,
+    HeldNotes(Vec<Note>),
+    HeldInputs(Vec<Hold>),
+    LatchedBinds(Vec<usize>),
     SaveConfig,
     Shutdown,
 }
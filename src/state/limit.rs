@@ -0,0 +1,96 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::{Bind, LimitConfig},
+    note::Note,
+};
+
+/// Keeps track of when binds have acted, to stop them acting more often than they're allowed to
+#[derive(Default)]
+pub struct RateLimiter {
+    /// MIDI timestamp each note last started at
+    note_starts: HashMap<Note, u64>,
+    /// Gap, in microseconds, between each note's latest start and the one before it
+    note_gaps: HashMap<Note, u64>,
+    /// When each bind, by index, last acted
+    bind_actions: HashMap<usize, Instant>,
+    /// When every bind action in the last second happened, oldest first
+    recent_actions: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    /// Record a note starting at the given MIDI timestamp
+    pub fn note_started(&mut self, note: &Note, timestamp: u64) {
+        if let Some(previous) = self.note_starts.insert(note.clone(), timestamp) {
+            self.note_gaps
+                .insert(note.clone(), timestamp.saturating_sub(previous));
+        }
+    }
+
+    /// Returns true if the bind at the given index is allowed to act now, and if so records it as having acted
+    pub fn try_act(&mut self, idx: usize, bind: &Bind, limits: &LimitConfig) -> bool {
+        let now = Instant::now();
+
+        // A note started again too quickly is treated as a bounce of the one before
+        if let Some(gap) = self.note_gaps.get(&bind.note) {
+            if *gap < u64::from(bind.debounce_ms) * 1000 {
+                return false;
+            }
+        }
+
+        if let Some(last) = self.bind_actions.get(&idx) {
+            if now.duration_since(*last) < Duration::from_millis(bind.cooldown_ms.into()) {
+                return false;
+            }
+        }
+
+        if !self.try_act_globally(now, limits) {
+            return false;
+        }
+
+        self.bind_actions.insert(idx, now);
+
+        true
+    }
+
+    /// Returns true if a bind is allowed to repeat its action now, and if so records it as having acted.
+    ///
+    /// Repeats are only limited by the global rate limit, as they're meant to happen quickly.
+    pub fn try_repeat(&mut self, limits: &LimitConfig) -> bool {
+        self.try_act_globally(Instant::now(), limits)
+    }
+
+    /// Returns true if any bind is allowed to act at the given time, and if so records an action as having happened
+    fn try_act_globally(&mut self, now: Instant, limits: &LimitConfig) -> bool {
+        while self
+            .recent_actions
+            .front()
+            .is_some_and(|x| now.duration_since(*x) >= Duration::from_secs(1))
+        {
+            self.recent_actions.pop_front();
+        }
+
+        if limits.max_actions_per_second != 0
+            && self.recent_actions.len() >= limits.max_actions_per_second.try_into().unwrap()
+        {
+            return false;
+        }
+
+        self.recent_actions.push_back(now);
+
+        true
+    }
+
+    /// Forget when binds last acted, such as when their indexes change
+    pub fn forget_binds(&mut self) {
+        self.bind_actions.clear();
+    }
+
+    /// Forget everything, as if nothing had been played
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
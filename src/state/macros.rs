@@ -0,0 +1,158 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    bind::{BindExecuteState, Executor, NoteEvent},
+    config::{BindAction, MacroBindAction, MacroStep},
+    note::Note,
+};
+
+/// A macro partway through being performed
+struct MacroRun {
+    /// The note event which started the macro, passed on to each step
+    event: NoteEvent,
+    /// The macro being performed
+    action: MacroBindAction,
+    /// Index of the next step to perform
+    next_step: usize,
+    /// How many more times the steps will be gone through after this time
+    repeats_left: u32,
+    /// When the next step is due
+    next_at: Instant,
+    /// Held actions started by the macro, to be released when it ends
+    held: Vec<BindAction>,
+}
+
+impl MacroRun {
+    /// Perform every step which is due by the given time, returning true once the macro has finished
+    fn advance(&mut self, executor: &mut Executor, now: Instant) -> anyhow::Result<bool> {
+        while self.next_at <= now {
+            let Some(step) = self.action.steps.get(self.next_step) else {
+                if self.repeats_left == 0 {
+                    self.release(executor)?;
+                    return Ok(true);
+                }
+
+                self.repeats_left -= 1;
+                self.next_step = 0;
+
+                // Don't spin forever on a macro with nothing in it
+                if self.action.steps.is_empty() {
+                    self.repeats_left = 0;
+                }
+
+                continue;
+            };
+
+            match step {
+                MacroStep::Action(action) => {
+                    executor.execute_action(action, &self.event, &BindExecuteState::Begin)?;
+
+                    if action.is_hold() {
+                        self.held.push(action.clone());
+                    } else {
+                        executor.execute_action(action, &self.event, &BindExecuteState::Release)?;
+                    }
+                }
+                MacroStep::Wait(ms) => {
+                    // Timed from when the step was due rather than now, so delays don't add up
+                    self.next_at += Duration::from_millis((*ms).into());
+                }
+            }
+
+            self.next_step += 1;
+        }
+
+        Ok(false)
+    }
+
+    /// Release everything held by the macro, most recent first
+    fn release(&mut self, executor: &mut Executor) -> anyhow::Result<()> {
+        for action in self.held.drain(..).rev() {
+            executor.execute_action(&action, &self.event, &BindExecuteState::Release)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Performs macros step by step in the background, so waiting between steps doesn't hold anything else up
+#[derive(Default)]
+pub struct MacroRunner {
+    /// Macros partway through being performed
+    runs: Vec<MacroRun>,
+}
+
+impl MacroRunner {
+    /// Start performing a macro, straight away doing every step up to the first wait
+    pub fn start(
+        &mut self,
+        executor: &mut Executor,
+        action: &MacroBindAction,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        let now = Instant::now();
+
+        let mut run = MacroRun {
+            event: event.clone(),
+            action: action.clone(),
+            next_step: 0,
+            repeats_left: action.repeat,
+            next_at: now,
+            held: vec![],
+        };
+
+        if !run.advance(executor, now)? {
+            self.runs.push(run);
+        }
+
+        Ok(())
+    }
+
+    /// Stop the macros started by the given note which are cancelled when it ends, releasing anything they were holding
+    pub fn cancel(&mut self, executor: &mut Executor, note: &Note) -> anyhow::Result<()> {
+        let (mut cancelled, runs) = std::mem::take(&mut self.runs)
+            .into_iter()
+            .partition(|x| x.action.cancel_on_release && x.event.note == *note);
+        self.runs = runs;
+
+        for run in &mut cancelled {
+            run.release(executor)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the soonest time a macro's next step is due, if any are running
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.runs.iter().map(|x| x.next_at).min()
+    }
+
+    /// Perform every step which is due by now, forgetting macros which have finished
+    pub fn advance(&mut self, executor: &mut Executor) -> anyhow::Result<()> {
+        let now = Instant::now();
+
+        let mut runs = std::mem::take(&mut self.runs);
+        let mut result = Ok(());
+
+        runs.retain_mut(|run| match run.advance(executor, now) {
+            Ok(finished) => !finished,
+            Err(e) => {
+                result = Err(e);
+                false
+            }
+        });
+
+        self.runs = runs;
+
+        result
+    }
+
+    /// Stop every macro, releasing anything they were holding
+    pub fn clear(&mut self, executor: &mut Executor) -> anyhow::Result<()> {
+        for mut run in std::mem::take(&mut self.runs) {
+            run.release(executor)?;
+        }
+
+        Ok(())
+    }
+}
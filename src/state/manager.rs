@@ -1,6 +1,10 @@
-use std::sync::{mpsc::Receiver, Arc};
-
-use crate::bind::Executor;
+use std::{
+    sync::{
+        mpsc::{Receiver, RecvTimeoutError},
+        Arc,
+    },
+    time::Instant,
+};
 
 use super::{
     interface::{
@@ -22,6 +26,7 @@ pub struct StateManager {
 impl StateManager {
     pub fn new(state: State) -> (Self, Arc<StateInterface>) {
         let (state_interface, recv_channel) = StateInterface::new();
+        state_interface.thru_routes_changed(&state.config.get_thru_routes());
 
         (
             Self {
@@ -36,7 +41,16 @@ impl StateManager {
     pub fn manage(&mut self) -> anyhow::Result<()> {
         self.state.init_midi("bindmidi")?;
 
-        while let Ok(message) = self.channel.recv() {
+        loop {
+            let message = match wait(&self.channel, self.state.next_deadline()) {
+                Wait::Message(message) => message,
+                Wait::Deadline => {
+                    self.state.handle_deadlines()?;
+                    continue;
+                }
+                Wait::Disconnected => break,
+            };
+
             match message.request {
                 req::LenBinds => {
                     message
@@ -75,7 +89,7 @@ impl StateManager {
                 req::DeleteActiveBind => {
                     message
                         .response_channel
-                        .send(res::DeleteActiveBind(self.state.delete_active_bind()))?;
+                        .send(res::DeleteActiveBind(self.state.delete_active_bind()?))?;
                 }
                 req::UpdateActiveBind(x) => {
                     message
@@ -97,7 +111,7 @@ impl StateManager {
                     message.response_channel.send(res::StartMidiConnection)?;
                 }
                 req::StopMidiConnection => {
-                    self.state.stop_midi_connection();
+                    self.state.stop_midi_connection()?;
                     message.response_channel.send(res::StopMidiConnection)?;
                 }
                 req::HasMidiConnection => {
@@ -105,33 +119,248 @@ impl StateManager {
                         .response_channel
                         .send(res::HasMidiConnection(self.state.has_midi_connection()))?;
                 }
-                req::ExecuteBindsForNote(note, vel, state) => {
-                    let binds = self.state.config.get_binds_for_note(&note);
-
-                    let mut executor = Executor::new();
-
-                    for bind in binds {
-                        executor.execute(&bind, vel, &note, &state)?;
-                    }
+                req::ExecuteBindsForNote(event, state, timestamp) => {
+                    self.state
+                        .execute_binds_for_note(&event, &state, timestamp)?;
 
                     message.response_channel.send(res::ExecuteBindsForNote)?;
                 }
+                req::TriggerString(x) => {
+                    message.response_channel.send(res::TriggerString(
+                        self.state.config.get_bind_trigger_string(x),
+                    ))?;
+                }
+                req::GestureConfig => {
+                    message
+                        .response_channel
+                        .send(res::GestureConfig(self.state.config.get_gesture_config()))?;
+                }
+                req::SetGestureConfig(x) => {
+                    self.state.config.set_gesture_config(x);
+                    message.response_channel.send(res::SetGestureConfig)?;
+                }
+                req::ControlChange(controller, value) => {
+                    self.state.control_change(controller, value)?;
+                    message.response_channel.send(res::ControlChange)?;
+                }
+                req::LayerString(x) => {
+                    message
+                        .response_channel
+                        .send(res::LayerString(self.state.config.get_bind_layer_string(x)))?;
+                }
+                req::Layers => {
+                    message
+                        .response_channel
+                        .send(res::Layers(self.state.config.get_layers()))?;
+                }
+                req::AddDefaultLayer => {
+                    message
+                        .response_channel
+                        .send(res::AddDefaultLayer(self.state.config.add_default_layer()))?;
+                }
+                req::UpdateLayer(idx, layer) => {
+                    message
+                        .response_channel
+                        .send(res::UpdateLayer(self.state.config.set_layer(idx, layer)))?;
+                }
+                req::DeleteLayer(x) => {
+                    message
+                        .response_channel
+                        .send(res::DeleteLayer(self.state.config.delete_layer(x)))?;
+                }
+                req::ForwardMidi(bytes) => {
+                    self.state.forward_midi(&bytes);
+                    message.response_channel.send(res::ForwardMidi)?;
+                }
+                req::ThruRoutes => {
+                    message
+                        .response_channel
+                        .send(res::ThruRoutes(self.state.config.get_thru_routes()))?;
+                }
+                req::AddDefaultThruRoute => {
+                    let idx = self.state.config.add_default_thru_route();
+                    self.thru_routes_changed();
+                    message
+                        .response_channel
+                        .send(res::AddDefaultThruRoute(idx))?;
+                }
+                req::UpdateThruRoute(idx, route) => {
+                    let result = self.state.config.set_thru_route(idx, route);
+                    self.thru_routes_changed();
+                    message
+                        .response_channel
+                        .send(res::UpdateThruRoute(result))?;
+                }
+                req::DeleteThruRoute(x) => {
+                    let result = self.state.config.delete_thru_route(x);
+                    self.thru_routes_changed();
+                    message
+                        .response_channel
+                        .send(res::DeleteThruRoute(result))?;
+                }
+                req::InputTransform => {
+                    message
+                        .response_channel
+                        .send(res::InputTransform(self.state.config.get_input_transform()))?;
+                }
+                req::SetInputTransform(x) => {
+                    self.state.config.set_input_transform(x);
+                    message.response_channel.send(res::SetInputTransform)?;
+                }
+                req::LimitConfig => {
+                    message
+                        .response_channel
+                        .send(res::LimitConfig(self.state.config.get_limit_config()))?;
+                }
+                req::SetLimitConfig(x) => {
+                    self.state.config.set_limit_config(x);
+                    message.response_channel.send(res::SetLimitConfig)?;
+                }
+                req::MidiOutputNames => {
+                    message
+                        .response_channel
+                        .send(res::MidiOutputNames(self.state.get_midi_output_names()))?;
+                }
+                req::FeedbackConfig => {
+                    message
+                        .response_channel
+                        .send(res::FeedbackConfig(self.state.get_feedback_config()))?;
+                }
+                req::SetFeedbackConfig(x) => {
+                    self.state.set_feedback_config(x);
+                    message.response_channel.send(res::SetFeedbackConfig)?;
+                }
+                req::IsCommandAllowed(x) => {
+                    message
+                        .response_channel
+                        .send(res::IsCommandAllowed(self.state.is_command_allowed(&x)))?;
+                }
+                req::SetCommandAllowed(x, allowed, path) => {
+                    self.state.set_command_allowed(&x, allowed, &path)?;
+                    message.response_channel.send(res::SetCommandAllowed)?;
+                }
+                req::Panic => {
+                    self.state.panic()?;
+                    message.response_channel.send(res::Panic)?;
+                }
+                req::PanicNote => {
+                    message
+                        .response_channel
+                        .send(res::PanicNote(self.state.config.get_panic_note()))?;
+                }
+                req::SetPanicNote(x) => {
+                    self.state.config.set_panic_note(x);
+                    message.response_channel.send(res::SetPanicNote)?;
+                }
+                req::HeldNotes => {
+                    message
+                        .response_channel
+                        .send(res::HeldNotes(self.state.get_held_notes()))?;
+                }
+                req::HeldInputs => {
+                    message
+                        .response_channel
+                        .send(res::HeldInputs(self.state.get_held_inputs()))?;
+                }
+                req::LatchedBinds => {
+                    message
+                        .response_channel
+                        .send(res::LatchedBinds(self.state.get_latched_binds()))?;
+                }
                 req::SaveConfig(path) => {
                     self.state.save_config(path)?;
                     message.response_channel.send(res::SaveConfig)?;
                 }
                 req::Shutdown => {
                     // Stop MIDI connection (if any)
-                    self.state.stop_midi_connection();
+                    self.state.stop_midi_connection()?;
 
                     message.response_channel.send(res::Shutdown)?;
 
                     // End this thread by returning
                     return Ok(());
                 }
-            };
+            }
         }
 
         Ok(())
     }
+
+    /// Let the interface know the thru routes have changed, so it knows which incoming MIDI is worth forwarding
+    fn thru_routes_changed(&self) {
+        self.interface
+            .thru_routes_changed(&self.state.config.get_thru_routes());
+    }
+}
+
+/// What happened while waiting for the next message
+enum Wait<T> {
+    Message(T),
+    /// Something is due to happen, so should be handled before any more messages
+    Deadline,
+    /// Nothing can send messages any more
+    Disconnected,
+}
+
+/// Wait for the next message, but wake up in time for anything waiting to happen.
+///
+/// Anything already due is handled first, as otherwise a steady stream of messages, such as MIDI from a busy
+/// controller, would keep it waiting forever
+fn wait<T>(channel: &Receiver<T>, deadline: Option<Instant>) -> Wait<T> {
+    let Some(deadline) = deadline else {
+        return channel.recv().map_or(Wait::Disconnected, Wait::Message);
+    };
+
+    let now = Instant::now();
+    if deadline <= now {
+        return Wait::Deadline;
+    }
+
+    match channel.recv_timeout(deadline - now) {
+        Ok(message) => Wait::Message(message),
+        Err(RecvTimeoutError::Timeout) => Wait::Deadline,
+        Err(RecvTimeoutError::Disconnected) => Wait::Disconnected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::mpsc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::{wait, Wait};
+
+    #[test]
+    fn handles_deadlines_while_messages_keep_arriving() {
+        let (sender, receiver) = mpsc::sync_channel(0);
+        thread::spawn(move || while sender.send(()).is_ok() {});
+
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(50);
+
+        loop {
+            match wait(&receiver, Some(deadline)) {
+                Wait::Message(()) => {
+                    assert!(started.elapsed() < Duration::from_secs(1));
+                }
+                Wait::Deadline => break,
+                Wait::Disconnected => panic!("the sender stopped"),
+            }
+        }
+
+        assert!(Instant::now() >= deadline);
+    }
+
+    #[test]
+    fn waits_for_messages_without_a_deadline() {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        sender.send(1).unwrap();
+        drop(sender);
+
+        assert!(matches!(wait(&receiver, None), Wait::Message(1)));
+        assert!(matches!(wait(&receiver, None), Wait::Disconnected));
+    }
 }
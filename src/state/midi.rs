@@ -4,7 +4,7 @@ use anyhow::bail;
 use midir::{MidiInput, MidiInputConnection};
 use midly::live::LiveEvent;
 
-use crate::note::Note;
+use crate::{bind::NoteEvent, note::Note};
 
 use super::interface::StateInterface;
 
@@ -18,6 +18,8 @@ pub struct Midi {
     port_names: Vec<String>,
     /// Possible MIDI connection
     connection: Option<MidiInputConnection<Arc<StateInterface>>>,
+    /// Name of the port the current MIDI connection is to
+    connected_port_name: Option<String>,
     /// Is there an open connection?
     pub has_open_connection: bool,
 }
@@ -37,6 +39,7 @@ impl Midi {
         let port = ports
             .get(self.port.unwrap_or(0))
             .expect("no midi input ports");
+        self.connected_port_name = input.port_name(port).ok();
 
         let connection = input
             .connect(port, conn_name, handle_midi_message, state_interface)
@@ -57,6 +60,7 @@ impl Midi {
 
         let connection = self.connection.take().expect("no open connection");
         self.has_open_connection = false;
+        self.connected_port_name = None;
 
         let (input, _) = connection.close();
 
@@ -100,6 +104,11 @@ impl Midi {
         Ok(self.port_names.clone())
     }
 
+    /// Returns the name of the port the current MIDI connection is to, if there is one
+    pub fn get_connected_port_name(&self) -> Option<&str> {
+        self.connected_port_name.as_deref()
+    }
+
     /// Set the stored Midi input port index to the given index
     pub fn set_midi_input_port(&mut self, idx: usize) -> anyhow::Result<()> {
         let ports = self.get_midi_input_names()?;
@@ -115,36 +124,49 @@ impl Midi {
 }
 
 fn handle_midi_message(
-    _timestamp: u64,
+    timestamp: u64,
     midi_data: &[u8],
     state_interface: &mut Arc<StateInterface>,
 ) {
     let event = LiveEvent::parse(midi_data).unwrap();
-    if let LiveEvent::Midi {
-        channel: _,
-        message,
-    } = event
-    {
+    if let LiveEvent::Midi { channel, message } = event {
         match message {
-            midly::MidiMessage::NoteOff { key, vel } => {
-                let note = Note::from_midi(key.as_int());
-
-                state_interface.execute_binds(
-                    note,
-                    vel.as_int(),
-                    crate::bind::BindExecuteState::Begin,
-                );
-            }
             midly::MidiMessage::NoteOn { key, vel } => {
-                let note = Note::from_midi(key.as_int());
+                let event = NoteEvent {
+                    note: Note::from_midi(key.as_int()),
+                    vel: vel.as_int(),
+                    channel: channel.as_int(),
+                };
+
+                // Many controllers end notes by starting them again with no velocity, rather than sending NoteOff
+                let state = if event.vel == 0 {
+                    crate::bind::BindExecuteState::Release
+                } else {
+                    crate::bind::BindExecuteState::Begin
+                };
+
+                state_interface.execute_binds(event, state, timestamp);
+            }
+            midly::MidiMessage::NoteOff { key, vel } => {
+                let event = NoteEvent {
+                    note: Note::from_midi(key.as_int()),
+                    vel: vel.as_int(),
+                    channel: channel.as_int(),
+                };
 
                 state_interface.execute_binds(
-                    note,
-                    vel.as_int(),
+                    event,
                     crate::bind::BindExecuteState::Release,
+                    timestamp,
                 );
             }
+            midly::MidiMessage::Controller { controller, value } => {
+                state_interface.control_change(controller.as_int(), value.as_int());
+            }
             _ => (),
         }
     }
+
+    // Everything is forwarded after it's acted on, so it's known whether notes were bound to anything
+    state_interface.forward_midi(midi_data);
 }
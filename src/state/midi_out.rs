@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use midir::{MidiOutput, MidiOutputConnection};
+
+use crate::{
+    bind::BindExecuteState,
+    config::{FeedbackConfig, MidiOutMessage, SendMidiBindAction},
+    note::Note,
+};
+
+/// Name of the virtual output port bindmidi makes itself, which is used when no port is chosen
+pub const VIRTUAL_PORT_NAME: &str = "bindmidi";
+
+/// MIDI status byte of a note starting, before the channel is added
+const NOTE_ON: u8 = 0x90;
+/// MIDI status byte of a note ending, before the channel is added
+const NOTE_OFF: u8 = 0x80;
+/// MIDI status byte of a control change, before the channel is added
+const CONTROL_CHANGE: u8 = 0xB0;
+/// MIDI status byte of a program change, before the channel is added
+const PROGRAM_CHANGE: u8 = 0xC0;
+
+/// Connections to MIDI output ports, made when they're first sent to
+#[derive(Default)]
+pub struct MidiOut {
+    /// Open connections, by the name of the port they're to, or `None` for bindmidi's own virtual port
+    connections: Vec<(Option<String>, MidiOutputConnection)>,
+    /// Notes lit up on the controller by feedback
+    lit_notes: HashSet<Note>,
+}
+
+impl MidiOut {
+    /// Get the names of all available MIDI output ports
+    pub fn get_midi_output_names() -> anyhow::Result<Vec<String>> {
+        let output = MidiOutput::new(VIRTUAL_PORT_NAME)?;
+
+        output
+            .ports()
+            .iter()
+            .map(|x| Ok(output.port_name(x)?))
+            .collect()
+    }
+
+    /// Send the message of a send MIDI action as it begins or is released. Notes end when they're released, but
+    /// everything else is only sent as the action begins
+    pub fn send_action(
+        &mut self,
+        action: &SendMidiBindAction,
+        state: &BindExecuteState,
+    ) -> anyhow::Result<()> {
+        let bytes = match (&action.message, state) {
+            (
+                MidiOutMessage::Note {
+                    channel,
+                    note,
+                    velocity,
+                },
+                BindExecuteState::Begin,
+            ) => vec![status(NOTE_ON, *channel), *note, *velocity],
+            (MidiOutMessage::Note { channel, note, .. }, BindExecuteState::Release) => {
+                vec![status(NOTE_OFF, *channel), *note, 0]
+            }
+            (
+                MidiOutMessage::ControlChange {
+                    channel,
+                    controller,
+                    value,
+                },
+                BindExecuteState::Begin,
+            ) => vec![status(CONTROL_CHANGE, *channel), *controller, *value],
+            (MidiOutMessage::ProgramChange { channel, program }, BindExecuteState::Begin) => {
+                vec![status(PROGRAM_CHANGE, *channel), *program]
+            }
+            (MidiOutMessage::Raw(bytes), BindExecuteState::Begin) => bytes.clone(),
+            (_, BindExecuteState::Release) => return Ok(()),
+        };
+
+        self.send(action.port.as_deref(), &bytes)
+    }
+
+    /// Light up a note's pad on the controller, or turn it off, if feedback is switched on
+    pub fn feedback(
+        &mut self,
+        config: &FeedbackConfig,
+        note: &Note,
+        lit: bool,
+    ) -> anyhow::Result<()> {
+        let Some(port) = &config.port else {
+            return Ok(());
+        };
+
+        let velocity = if lit {
+            self.lit_notes.insert(note.clone());
+            config.lit_velocity
+        } else {
+            self.lit_notes.remove(note);
+            config.unlit_velocity
+        };
+
+        // Controllers light their pads by being sent notes, with the velocity choosing the colour
+        self.send(
+            Some(port),
+            &[status(NOTE_ON, config.channel), note.get_midi(), velocity],
+        )
+    }
+
+    /// Turn off every pad lit up by feedback
+    pub fn clear_feedback(&mut self, config: &FeedbackConfig) -> anyhow::Result<()> {
+        for note in std::mem::take(&mut self.lit_notes) {
+            self.feedback(config, &note, false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Send raw bytes to an output port, connecting to it first if needed
+    pub fn send(&mut self, port: Option<&str>, bytes: &[u8]) -> anyhow::Result<()> {
+        let idx = if let Some(idx) = self
+            .connections
+            .iter()
+            .position(|(x, _)| x.as_deref() == port)
+        {
+            idx
+        } else {
+            let connection = connect(port)?;
+            self.connections.push((port.map(Into::into), connection));
+            self.connections.len() - 1
+        };
+
+        self.connections[idx].1.send(bytes)?;
+
+        Ok(())
+    }
+
+    /// Close every connection
+    pub fn clear(&mut self) {
+        for (_, connection) in self.connections.drain(..) {
+            connection.close();
+        }
+    }
+}
+
+/// Returns the status byte of a kind of message sent on the given channel, counting from 0
+fn status(kind: u8, channel: u8) -> u8 {
+    kind | (channel & 0x0F)
+}
+
+/// Connect to the output port with the given name, or make bindmidi's own virtual port if no name is given
+fn connect(port: Option<&str>) -> anyhow::Result<MidiOutputConnection> {
+    let output = MidiOutput::new(VIRTUAL_PORT_NAME)?;
+
+    let Some(name) = port else {
+        return connect_virtual(output);
+    };
+
+    let ports = output.ports();
+    let port = ports
+        .iter()
+        .find(|x| output.port_name(x).is_ok_and(|x| x == name))
+        .ok_or_else(|| anyhow!("there's no MIDI output called `{name}`"))?;
+
+    output
+        .connect(port, VIRTUAL_PORT_NAME)
+        .map_err(|e| anyhow!("couldn't connect to `{name}`: {e}"))
+}
+
+/// Make bindmidi's own virtual output port, which other programs can connect to
+#[cfg(unix)]
+fn connect_virtual(output: MidiOutput) -> anyhow::Result<MidiOutputConnection> {
+    use midir::os::unix::VirtualOutput;
+
+    output
+        .create_virtual(VIRTUAL_PORT_NAME)
+        .map_err(|e| anyhow!("couldn't make a virtual MIDI output: {e}"))
+}
+
+/// Make bindmidi's own virtual output port, which other programs can connect to
+#[cfg(not(unix))]
+fn connect_virtual(_output: MidiOutput) -> anyhow::Result<MidiOutputConnection> {
+    anyhow::bail!(
+        "virtual MIDI outputs aren't supported on this platform, so an output port must be chosen"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn sends_actions_and_feedback() {
+        use std::{sync::mpsc, time::Duration};
+
+        use midir::{os::unix::VirtualInput, MidiInput};
+
+        use super::MidiOut;
+        use crate::{
+            bind::BindExecuteState,
+            config::{FeedbackConfig, MidiOutMessage, SendMidiBindAction},
+            note::Note,
+        };
+
+        const PORT_NAME: &str = "bindmidi test";
+
+        // Machines without ALSA's sequencer, such as most CI runners, can't make a port to send to
+        let (sender, receiver) = mpsc::channel();
+        let connection = MidiInput::new(PORT_NAME).ok().and_then(|x| {
+            x.create_virtual(
+                PORT_NAME,
+                move |_, bytes, ()| {
+                    let _ = sender.send(bytes.to_vec());
+                },
+                (),
+            )
+            .ok()
+        });
+        let Some(_connection) = connection else {
+            eprintln!("Skipped, as there's no ALSA sequencer to make a virtual port with");
+            return;
+        };
+
+        let port = MidiOut::get_midi_output_names()
+            .unwrap()
+            .into_iter()
+            .find(|x| x.contains(PORT_NAME))
+            .expect("the virtual port should be listed");
+        let receive = || receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+
+        let mut out = MidiOut::default();
+        let send = |out: &mut MidiOut, message, state| {
+            let action = SendMidiBindAction {
+                port: Some(port.clone()),
+                message,
+            };
+            out.send_action(&action, &state).unwrap();
+        };
+
+        let note = MidiOutMessage::Note {
+            channel: 2,
+            note: 60,
+            velocity: 100,
+        };
+        send(&mut out, note.clone(), BindExecuteState::Begin);
+        assert_eq!(receive(), [0x92, 60, 100]);
+        send(&mut out, note, BindExecuteState::Release);
+        assert_eq!(receive(), [0x82, 60, 0]);
+
+        // Everything but notes is only sent as the action begins
+        let control_change = MidiOutMessage::ControlChange {
+            channel: 0,
+            controller: 7,
+            value: 64,
+        };
+        send(&mut out, control_change.clone(), BindExecuteState::Begin);
+        send(&mut out, control_change, BindExecuteState::Release);
+        assert_eq!(receive(), [0xB0, 7, 64]);
+
+        send(
+            &mut out,
+            MidiOutMessage::Raw(vec![0xC1, 5]),
+            BindExecuteState::Begin,
+        );
+        assert_eq!(receive(), [0xC1, 5]);
+
+        let feedback = FeedbackConfig {
+            port: Some(port.clone()),
+            channel: 9,
+            lit_velocity: 127,
+            unlit_velocity: 0,
+        };
+        out.feedback(&feedback, &Note::from_midi(36), true).unwrap();
+        assert_eq!(receive(), [0x99, 36, 127]);
+        out.clear_feedback(&feedback).unwrap();
+        assert_eq!(receive(), [0x99, 36, 0]);
+
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+}
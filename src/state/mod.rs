@@ -1,33 +1,143 @@
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
 
 use serde_json::to_string;
 
-use crate::config::{Bind, Config};
+use crate::{
+    allowlist::CommandAllowlist,
+    bind::{BindExecuteState, Executor, NoteEvent, ScrollRemainder},
+    config::{
+        Bind, BindAction, BindTrigger, CommandBindAction, Config, FeedbackConfig, LayerActivation,
+        SendMidiBindAction, TextBindAction,
+    },
+    held::{HeldInputs, Hold},
+    monitor::Monitors,
+    note::Note,
+    osc::OscSender,
+    text::typing_macro,
+};
 
-use self::{interface::StateInterface, midi::Midi};
+use self::{
+    choice::Choices,
+    commands::CommandRunner,
+    gesture::GestureDetector,
+    glide::GlideRunner,
+    input::{InputState, SUSTAIN_CONTROLLER},
+    interface::StateInterface,
+    limit::RateLimiter,
+    macros::MacroRunner,
+    midi::Midi,
+    midi_out::MidiOut,
+    motion::MotionRunner,
+    pressed::PressedBinds,
+    repeat::AutoRepeater,
+    thru::Thru,
+    watchdog::HoldWatchdog,
+};
 
+pub mod choice;
+pub mod commands;
+pub mod gesture;
+pub mod glide;
+pub mod input;
 pub mod interface;
+pub mod limit;
+pub mod macros;
 pub mod manager;
 pub mod midi;
+pub mod midi_out;
+pub mod motion;
+pub mod pressed;
+pub mod repeat;
 pub mod table_data_adaptor;
+pub mod thru;
+pub mod watchdog;
 
 /// App data used at runtime
 pub struct State {
     /// Persistent app data stored in a file
     config: Config,
+    /// Commands allowed to be run on this computer, stored in a separate file to the config
+    allowlist: CommandAllowlist,
     /// Index of the bind currently being edited in the GUI
     active_bind: Option<usize>,
     /// Runtime MIDI configuration/data
     midi: Midi,
+    /// Connections to MIDI outputs, for sending MIDI and lighting up pads on the controller
+    midi_out: MidiOut,
+    /// Forwards incoming MIDI on to other outputs
+    thru: Thru,
+    /// Tracks notes being played as gestures
+    gestures: GestureDetector,
+    /// What is currently being played on the MIDI input
+    input: InputState,
+    /// Keys and mouse buttons being held down, and the notes holding them
+    held: HeldInputs,
+    /// Precise scrolling which hasn't added up to a whole line yet
+    scroll: ScrollRemainder,
+    /// The monitors connected, for positions on a monitor
+    monitors: Monitors,
+    /// Where OSC messages are sent from
+    osc: OscSender,
+    /// Stops binds acting more often than they're allowed to
+    limiter: RateLimiter,
+    /// Macros partway through being performed
+    macros: MacroRunner,
+    /// Continuous movement of the mouse while notes are held
+    motions: MotionRunner,
+    /// Smooth mouse movements and drags, which carry on after their notes end
+    glides: GlideRunner,
+    /// Programs started by command binds
+    commands: CommandRunner,
+    /// What cycle and random binds have chosen
+    choices: Choices,
+    /// Repeats binds' actions while their notes are held
+    repeater: AutoRepeater,
+    /// Releases notes held for longer than they're allowed to be
+    watchdog: HoldWatchdog,
+    /// The note each held note became after the input transform, so it ends the same way even if the transform changes
+    transformed_notes: HashMap<Note, Note>,
+    /// Binds started by each held note, so the same ones are released when it ends
+    pressed_binds: PressedBinds,
+    /// Binds whose notes have ended, but are kept held until the sustain pedal is lifted, with the event they ended with
+    sustained_binds: Vec<(NoteEvent, Bind)>,
+    /// Toggle binds which are latched until their note is pressed again, by index, with the event which latched them
+    latched_binds: HashMap<usize, (NoteEvent, Bind)>,
 }
 
 impl State {
     /// Create a new State by providing the existing persistent data
-    pub fn from_config(config: Config) -> Self {
+    pub fn from_config(config: Config, allowlist: CommandAllowlist) -> Self {
         State {
             config,
+            allowlist,
             active_bind: None,
             midi: Midi::default(),
+            midi_out: MidiOut::default(),
+            thru: Thru::default(),
+            gestures: GestureDetector::default(),
+            input: InputState::default(),
+            held: HeldInputs::default(),
+            scroll: ScrollRemainder::default(),
+            monitors: Monitors::default(),
+            osc: OscSender::default(),
+            limiter: RateLimiter::default(),
+            macros: MacroRunner::default(),
+            motions: MotionRunner::default(),
+            glides: GlideRunner::default(),
+            commands: CommandRunner::default(),
+            choices: Choices::default(),
+            repeater: AutoRepeater::default(),
+            watchdog: HoldWatchdog::default(),
+            transformed_notes: HashMap::new(),
+            pressed_binds: PressedBinds::default(),
+            sustained_binds: vec![],
+            latched_binds: HashMap::new(),
         }
     }
 
@@ -42,18 +152,201 @@ impl State {
         Ok(())
     }
 
+    /// Is the command allowed to be run by binds on this computer?
+    fn is_command_allowed(&self, command: &CommandBindAction) -> bool {
+        self.allowlist.is_allowed(command)
+    }
+
+    /// Allow or disallow a command being run by binds, saving the allowlist to a file
+    fn set_command_allowed(
+        &mut self,
+        command: &CommandBindAction,
+        allowed: bool,
+        path: &Path,
+    ) -> anyhow::Result<()> {
+        if allowed {
+            self.allowlist.allow(command);
+        } else {
+            self.allowlist.disallow(command);
+        }
+
+        self.allowlist.save(path)
+    }
+
+    /// Run a command bind's program, if it's been allowed to run.
+    ///
+    /// Problems running it, such as a missing program, are only logged
+    fn start_command(&mut self, action: &CommandBindAction, note: &Note) {
+        if !self.allowlist.is_allowed(action) {
+            eprintln!(
+                "Warning: not running `{}`, as it hasn't been allowed to run on this computer",
+                action.program
+            );
+            return;
+        }
+
+        if let Err(e) = self.commands.start(action, note) {
+            eprintln!("Couldn't run `{}`: {e}", action.program);
+        }
+    }
+
     /// Start MIDI connection
     fn start_midi_connection(
         &mut self,
         conn_name: &str,
         state_interface: Arc<StateInterface>,
     ) -> anyhow::Result<()> {
-        self.midi.start_midi_connection(conn_name, state_interface)
+        self.midi
+            .start_midi_connection(conn_name, state_interface)?;
+
+        // Monitors are most likely to have been plugged in or out while nothing was connected
+        self.executor().refresh_monitors();
+
+        // Some cycles start from their first action again each time they're connected
+        let resetting: Vec<usize> = (0..self.config.len_binds())
+            .filter(|x| {
+                matches!(
+                    self.config.get_bind(*x).map(|x| x.action),
+                    Ok(BindAction::Cycle(action)) if action.reset_on_connect
+                )
+            })
+            .collect();
+        self.choices.reset_cycles(resetting.iter().copied());
+
+        for idx in resetting {
+            self.persist_cycle_position(idx);
+        }
+
+        Ok(())
     }
 
     /// Stop MIDI connection
-    fn stop_midi_connection(&mut self) {
+    fn stop_midi_connection(&mut self) -> anyhow::Result<()> {
         self.midi.stop_midi_connection();
+
+        // Nothing should be left held once nothing can release it
+        self.panic()?;
+        self.midi_out.clear();
+        self.thru.clear();
+
+        self.input.clear();
+        self.limiter.clear();
+        self.transformed_notes.clear();
+
+        Ok(())
+    }
+
+    /// Release every key and mouse button being held, and stop everything in progress, such as macros and repeats
+    fn panic(&mut self) -> anyhow::Result<()> {
+        let mut executor = self.executor();
+
+        self.macros.clear(&mut executor)?;
+        self.motions.clear();
+        self.glides.clear();
+        self.repeater.clear();
+        self.watchdog.clear();
+        self.gestures.clear();
+        self.pressed_binds.clear();
+        self.sustained_binds.clear();
+        self.latched_binds.clear();
+
+        let feedback = self.config.get_feedback_config();
+        if let Err(e) = self.midi_out.clear_feedback(&feedback) {
+            eprintln!("Couldn't turn off pads on the controller: {e}");
+        }
+
+        if let Err(e) = executor.release_everything() {
+            eprintln!("Couldn't release everything being held: {e}");
+        }
+
+        Ok(())
+    }
+
+    /// Send the MIDI of a send MIDI action as it begins or is released.
+    ///
+    /// If its output port can't be opened or has gone away, this is logged and the message is dropped
+    fn send_midi(&mut self, action: &SendMidiBindAction, state: &BindExecuteState) {
+        if let Err(e) = self.midi_out.send_action(action, state) {
+            eprintln!("Couldn't send MIDI: {e}");
+        }
+    }
+
+    /// Start typing a text action's text one character at a time in the background, as a macro waiting between each.
+    ///
+    /// If the text can't be parsed, such as when a `{` is never closed, this is logged and nothing is typed
+    fn start_typing(
+        &mut self,
+        executor: &mut Executor,
+        action: &TextBindAction,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        match typing_macro(action, event) {
+            Ok(typing) => self.macros.start(executor, &typing, event),
+            Err(e) => {
+                eprintln!("Couldn't type `{}`: {e}", action.text);
+                Ok(())
+            }
+        }
+    }
+
+    /// Forward an incoming MIDI message on through the MIDI thru routes.
+    ///
+    /// Each route is sent to separately, so one whose output can't be opened is logged and skipped while the others
+    /// still get the message
+    fn forward_midi(&mut self, bytes: &[u8]) {
+        let routes = self.config.get_thru_routes();
+        let messages = self
+            .thru
+            .route(&routes, self.midi.get_connected_port_name(), bytes);
+
+        for (port, bytes) in messages {
+            if let Err(e) = self.midi_out.send(port.as_deref(), &bytes) {
+                eprintln!("Couldn't forward MIDI: {e}");
+            }
+        }
+    }
+
+    /// Light up a note's pad on the controller, or turn it off, if feedback is switched on
+    fn feedback(&mut self, note: &Note, lit: bool) {
+        let config = self.config.get_feedback_config();
+
+        if let Err(e) = self.midi_out.feedback(&config, note, lit) {
+            eprintln!("Couldn't light up {note} on the controller: {e}");
+        }
+    }
+
+    /// Get the names of all available MIDI output ports
+    #[allow(clippy::unused_self)]
+    fn get_midi_output_names(&self) -> anyhow::Result<Vec<String>> {
+        MidiOut::get_midi_output_names()
+    }
+
+    /// Returns a clone of the config for lighting up pads on the controller
+    fn get_feedback_config(&self) -> FeedbackConfig {
+        self.config.get_feedback_config()
+    }
+
+    /// Replace the config for lighting up pads on the controller, turning off any pads lit up with the old one
+    fn set_feedback_config(&mut self, feedback: FeedbackConfig) {
+        if let Err(e) = self
+            .midi_out
+            .clear_feedback(&self.config.get_feedback_config())
+        {
+            eprintln!("Couldn't turn off pads on the controller: {e}");
+        }
+
+        self.config.set_feedback_config(feedback);
+    }
+
+    /// Returns an executor sharing what's held down, scrolled, known of the monitors and sent OSC from with every
+    /// other one
+    fn executor(&self) -> Executor {
+        Executor::new(&self.held, &self.scroll, &self.monitors, &self.osc)
+    }
+
+    /// Returns a handle to the keys and mouse buttons being held down, which can release them from anywhere
+    pub fn held_inputs(&self) -> HeldInputs {
+        self.held.clone()
     }
 
     /// Returns true if there is an active midi connection, false if not
@@ -77,6 +370,564 @@ impl State {
         Ok(())
     }
 
+    /// Execute the binds for a note starting or ending.
+    ///
+    /// Binds with a [`BindTrigger::Press`] trigger execute straight away, but those with other triggers
+    /// wait until the gesture is recognised, which may be some time after the note ends.
+    fn execute_binds_for_note(
+        &mut self,
+        event: &NoteEvent,
+        state: &BindExecuteState,
+        timestamp: u64,
+    ) -> anyhow::Result<()> {
+        // The panic note is recognised as played, before anything's changed by the transform
+        if self.config.get_panic_note().as_ref() == Some(&event.note) {
+            if let BindExecuteState::Begin = state {
+                self.thru.note_bound(&event.note);
+                self.panic()?;
+            }
+
+            return Ok(());
+        }
+
+        let input_note = event.note.clone();
+        let Some(event) = self.transform_event(event, state) else {
+            return Ok(());
+        };
+        let note = &event.note;
+
+        match state {
+            BindExecuteState::Begin => {
+                self.input.press(note);
+                self.limiter.note_started(note, timestamp);
+            }
+            BindExecuteState::Release => self.input.release(note),
+        }
+
+        // Notes which switch layers don't execute any binds themselves
+        if let Some(layer) = self.config.get_layer_switched_by(note) {
+            if let BindExecuteState::Begin = state {
+                self.thru.note_bound(&input_note);
+            }
+
+            if let (LayerActivation::ToggledByNote(_), BindExecuteState::Begin) =
+                (&layer.activation, state)
+            {
+                self.input.toggle_layer(&layer.name);
+            }
+
+            return Ok(());
+        }
+
+        let binds = self.get_binds_for_note(note);
+
+        if let (false, BindExecuteState::Begin) = (binds.is_empty(), state) {
+            self.thru.note_bound(&input_note);
+        }
+
+        let mut executor = self.executor();
+
+        match state {
+            BindExecuteState::Begin => {
+                let limits = self.config.get_limit_config();
+
+                let acting: Vec<(usize, Bind)> = binds
+                    .iter()
+                    .filter(|(idx, x)| {
+                        x.trigger == BindTrigger::Press && self.limiter.try_act(*idx, x, &limits)
+                    })
+                    .cloned()
+                    .collect();
+
+                // Like a piano, playing a sustained note again takes over from the pedal, so what the pedal was holding
+                // is let go before the note starts again
+                let (retaken, sustained) = std::mem::take(&mut self.sustained_binds)
+                    .into_iter()
+                    .partition(|(x, _)| x.note == *note);
+                self.sustained_binds = sustained;
+                for (sustained_event, bind) in retaken {
+                    self.release_bind(&mut executor, &bind, &sustained_event)?;
+                }
+
+                // The note started again without ending, such as when its end was lost, so what it started before is
+                // let go of the same way before it's started again. If nothing acts this time, it's left held instead
+                if !acting.is_empty() {
+                    self.repeater.stop(note);
+                    self.watchdog.stop(note);
+
+                    for (_, bind) in self.pressed_binds.end(note) {
+                        self.release_bind(&mut executor, &bind, &event)?;
+                    }
+                }
+
+                let mut pressed = vec![];
+
+                for (idx, bind) in acting {
+                    if let Some(group) = &bind.group {
+                        self.release_group(&mut executor, group, idx, &event)?;
+                    }
+
+                    // A latched toggle bind is released as whatever it chose when it was latched, rather than choosing again
+                    let bind = match self.latched_binds.get(&idx) {
+                        Some((_, latched)) => latched.clone(),
+                        None => self.choose_action(idx, bind),
+                    };
+
+                    // Toggle binds carry on after the note ends, so aren't released with it
+                    if bind.toggle && bind.action.is_hold() {
+                        self.toggle_bind(&mut executor, idx, bind, &event)?;
+                        continue;
+                    }
+
+                    self.begin_bind(&mut executor, &bind, &event)?;
+                    self.repeater.start(idx, &bind, &event);
+
+                    // Each bind is only released by its own limit, leaving the note's other binds held
+                    if let Some(max_hold) = limits.max_hold(&bind) {
+                        self.watchdog.start(&event, idx, max_hold);
+                    }
+
+                    pressed.push((idx, bind));
+                }
+
+                self.pressed_binds.start(note, pressed);
+            }
+            BindExecuteState::Release => {
+                self.repeater.stop(note);
+                self.watchdog.stop(note);
+
+                // The active layers may have changed since the note started, but whatever it started should still be released
+                for (_, bind) in self.pressed_binds.end(note) {
+                    if bind.sustain && bind.action.is_hold() && self.input.is_sustained() {
+                        self.sustained_binds.push((event.clone(), bind));
+                    } else {
+                        self.release_bind(&mut executor, &bind, &event)?;
+                    }
+                }
+            }
+        }
+
+        // Gestures are only tracked for notes with a bind using a gesture trigger, as plain presses don't need them
+        if binds.iter().all(|(_, x)| x.trigger == BindTrigger::Press) {
+            return Ok(());
+        }
+
+        let gesture_config = self.config.get_gesture_config();
+
+        let gesture = match state {
+            BindExecuteState::Begin => self.gestures.press(&event, timestamp, &gesture_config),
+            BindExecuteState::Release => {
+                // A tap can be acted on immediately if there's no double tap it could turn into
+                let await_double_tap = binds
+                    .iter()
+                    .any(|(_, x)| x.trigger == BindTrigger::DoubleTap);
+
+                self.gestures
+                    .release(note, timestamp, await_double_tap, &gesture_config)
+            }
+        };
+
+        if let Some((trigger, event)) = gesture {
+            self.execute_gesture_binds(&mut executor, &event, trigger)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply the input transform to a note starting or ending, returning `None` if the note is moved out of range
+    fn transform_event(
+        &mut self,
+        event: &NoteEvent,
+        state: &BindExecuteState,
+    ) -> Option<NoteEvent> {
+        let transform = self.config.get_input_transform();
+
+        // A note ending becomes the same note it did when it started, even if the transform has changed since then
+        let note = match state {
+            BindExecuteState::Begin => None,
+            BindExecuteState::Release => self.transformed_notes.remove(&event.note),
+        };
+
+        let note = match note {
+            Some(note) => note,
+            None => Note::from_midi(
+                transform.note(event.note.get_midi(), self.midi.get_connected_port_name())?,
+            ),
+        };
+
+        if let BindExecuteState::Begin = state {
+            self.transformed_notes
+                .insert(event.note.clone(), note.clone());
+        }
+
+        Some(NoteEvent {
+            note,
+            vel: transform.velocity_curve.apply(event.vel),
+            channel: transform.channel(event.channel),
+        })
+    }
+
+    /// Execute the binds for a note which are triggered by the given gesture, as a momentary press
+    fn execute_gesture_binds(
+        &mut self,
+        executor: &mut Executor,
+        event: &NoteEvent,
+        trigger: BindTrigger,
+    ) -> anyhow::Result<()> {
+        let limits = self.config.get_limit_config();
+
+        for (idx, bind) in self.get_binds_for_note(&event.note) {
+            if bind.trigger != trigger || !self.limiter.try_act(idx, &bind, &limits) {
+                continue;
+            }
+
+            if let Some(group) = &bind.group {
+                self.release_group(executor, group, idx, event)?;
+            }
+
+            let bind = self.choose_action(idx, bind);
+            self.execute_momentary(executor, &bind, event)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the bind at the given index acting as whichever action it chooses, if it's a cycle or random bind, or the
+    /// bind itself otherwise.
+    ///
+    /// The chosen action is what's started, held and released, so a note ends whatever it started even though the
+    /// bind will have moved on to something else by then
+    fn choose_action(&mut self, idx: usize, mut bind: Bind) -> Bind {
+        let chosen = match &mut bind.action {
+            BindAction::Cycle(action) => {
+                let chosen = self.choices.cycle(idx, action);
+                if action.persist {
+                    self.persist_cycle_position(idx);
+                }
+
+                chosen.map(|x| action.actions.swap_remove(x))
+            }
+            BindAction::Random(action) => self
+                .choices
+                .random(idx, action)
+                .map(|x| action.choices.swap_remove(x).action),
+            _ => None,
+        };
+
+        if let Some(chosen) = chosen {
+            bind.action = chosen;
+        }
+
+        bind
+    }
+
+    /// Keep where a persisted cycle bind is up to in the config, so it carries on from there once the config's been saved
+    /// and bindmidi is restarted
+    fn persist_cycle_position(&mut self, idx: usize) {
+        let Some(position) = self.choices.cycle_position(idx) else {
+            return;
+        };
+        let Ok(mut bind) = self.config.get_bind(idx) else {
+            return;
+        };
+
+        if let BindAction::Cycle(action) = &mut bind.action {
+            if action.persist {
+                action.position = position;
+                self.config
+                    .set_bind(idx, bind)
+                    .expect("bind should still be in-bounds");
+            }
+        }
+    }
+
+    /// Execute a bind's action once, releasing it straight away.
+    ///
+    /// Macros started this way run to the end, as there's no note being held to cancel them.
+    fn execute_momentary(
+        &mut self,
+        executor: &mut Executor,
+        bind: &Bind,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        match &bind.action {
+            BindAction::Macro(action) => return self.macros.start(executor, action, event),
+            // Started programs are left to run, as there's no note being held to stop them
+            BindAction::Command(action) => {
+                self.start_command(action, &event.note);
+                return Ok(());
+            }
+            BindAction::SendMidi(action) => {
+                self.send_midi(action, &BindExecuteState::Begin);
+                self.send_midi(action, &BindExecuteState::Release);
+                return Ok(());
+            }
+            BindAction::Text(action) if action.char_delay_ms > 0 => {
+                self.start_typing(executor, action, event)?;
+                return Ok(());
+            }
+            // Glides carry on until they've finished anyway, as they don't stop when their note ends
+            BindAction::Drag(action) => return self.glides.start_drag(executor, action, event),
+            BindAction::GlideMouseRelative(action) => {
+                return self.glides.start_relative(executor, action, event);
+            }
+            BindAction::GlideMouseAbsolute(action) => {
+                return self.glides.start_absolute(executor, action, event);
+            }
+            _ => {}
+        }
+
+        // Release actions only happen when a held note ends
+        executor.execute_action(&bind.action, event, &BindExecuteState::Begin)?;
+        executor.execute_action(&bind.action, event, &BindExecuteState::Release)
+    }
+
+    /// Start a bind's action, in the background if it's a macro or continuous
+    fn begin_bind(
+        &mut self,
+        executor: &mut Executor,
+        bind: &Bind,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        match &bind.action {
+            BindAction::Macro(action) => self.macros.start(executor, action, event)?,
+            BindAction::MoveMouseContinuous(action) => {
+                self.motions.start_move(executor, action, event)?;
+            }
+            BindAction::ScrollContinuous(action) => {
+                self.motions.start_scroll(executor, action, event)?;
+            }
+            BindAction::Command(action) => self.start_command(action, &event.note),
+            BindAction::SendMidi(action) => self.send_midi(action, &BindExecuteState::Begin),
+            BindAction::Text(action) if action.char_delay_ms > 0 => {
+                self.start_typing(executor, action, event)?;
+            }
+            BindAction::Drag(action) => self.glides.start_drag(executor, action, event)?,
+            BindAction::GlideMouseRelative(action) => {
+                self.glides.start_relative(executor, action, event)?;
+            }
+            BindAction::GlideMouseAbsolute(action) => {
+                self.glides.start_absolute(executor, action, event)?;
+            }
+            _ => executor.execute(bind, event, &BindExecuteState::Begin)?,
+        }
+
+        if bind.feedback {
+            self.feedback(&bind.note, true);
+        }
+
+        Ok(())
+    }
+
+    /// Latch a toggle bind's held action if it isn't already, or release it if it is
+    fn toggle_bind(
+        &mut self,
+        executor: &mut Executor,
+        idx: usize,
+        bind: Bind,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        if let Some((_, latched)) = self.latched_binds.remove(&idx) {
+            return self.release_bind(executor, &latched, event);
+        }
+
+        self.begin_bind(executor, &bind, event)?;
+        self.latched_binds.insert(idx, (event.clone(), bind));
+
+        Ok(())
+    }
+
+    /// Release everything held by binds in an exclusive group, other than by the bind at the given index or the event's note,
+    /// as that bind is about to act
+    fn release_group(
+        &mut self,
+        executor: &mut Executor,
+        group: &str,
+        idx: usize,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        let in_group = |x: &Bind| x.action.is_hold() && x.group.as_deref() == Some(group);
+
+        // Released as if by the notes holding them, so the holds are let go of properly
+        let mut released = vec![];
+
+        for (note, bind) in self.pressed_binds.take_matching(&event.note, in_group) {
+            let note_event = NoteEvent {
+                note,
+                ..event.clone()
+            };
+            released.push((note_event, bind));
+        }
+
+        self.latched_binds.retain(|x, (latched_event, bind)| {
+            if *x != idx && in_group(bind) {
+                released.push((latched_event.clone(), bind.clone()));
+                return false;
+            }
+
+            true
+        });
+
+        self.sustained_binds.retain(|(sustained_event, bind)| {
+            if in_group(bind) {
+                released.push((sustained_event.clone(), bind.clone()));
+                return false;
+            }
+
+            true
+        });
+
+        for (event, bind) in released {
+            self.release_bind(executor, &bind, &event)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the MIDI notes currently held down
+    fn get_held_notes(&self) -> Vec<Note> {
+        self.input.held_notes()
+    }
+
+    /// Returns the keys and mouse buttons currently held down, along with the notes holding them
+    fn get_held_inputs(&self) -> Vec<Hold> {
+        self.held.holds()
+    }
+
+    /// Returns the indexes of the toggle binds which are currently latched
+    fn get_latched_binds(&self) -> Vec<usize> {
+        self.latched_binds.keys().copied().collect()
+    }
+
+    /// Release a bind's action, stopping it if it's running in the background until its note ends
+    fn release_bind(
+        &mut self,
+        executor: &mut Executor,
+        bind: &Bind,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        match &bind.action {
+            BindAction::Macro(_) => self.macros.cancel(executor, &event.note)?,
+            BindAction::MoveMouseContinuous(_) | BindAction::ScrollContinuous(_) => {
+                self.motions.stop(&event.note);
+            }
+            BindAction::Command(action) => {
+                if let Err(e) = self.commands.release(&event.note) {
+                    eprintln!("Couldn't stop `{}`: {e}", action.program);
+                }
+            }
+            BindAction::SendMidi(action) => self.send_midi(action, &BindExecuteState::Release),
+            _ => executor.execute_action(&bind.action, event, &BindExecuteState::Release)?,
+        }
+
+        executor.execute_release_action(bind, event)?;
+
+        if bind.feedback {
+            self.feedback(&bind.note, false);
+        }
+
+        Ok(())
+    }
+
+    /// Returns clones of the binds for the given note in the currently active layers, along with their indexes
+    fn get_binds_for_note(&self, note: &Note) -> Vec<(usize, Bind)> {
+        let active_layers = self.input.active_layers(&self.config.get_layers());
+
+        self.config.get_binds_for_note(note, &active_layers)
+    }
+
+    /// Handle a MIDI control change message
+    fn control_change(&mut self, controller: u8, value: u8) -> anyhow::Result<()> {
+        if controller == SUSTAIN_CONTROLLER {
+            self.input.set_sustain(value);
+
+            // Lifting the pedal releases everything it was keeping held
+            if !self.input.is_sustained() {
+                let mut executor = self.executor();
+
+                for (event, bind) in std::mem::take(&mut self.sustained_binds) {
+                    self.release_bind(&mut executor, &bind, &event)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the next time something is waiting to happen, if anything is
+    fn next_deadline(&self) -> Option<Instant> {
+        [
+            self.gestures.next_deadline(),
+            self.macros.next_deadline(),
+            self.motions.next_deadline(),
+            self.glides.next_deadline(),
+            self.repeater.next_deadline(),
+            self.watchdog.next_deadline(),
+            self.commands.next_deadline(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// Do whatever was waiting to happen by now
+    fn handle_deadlines(&mut self) -> anyhow::Result<()> {
+        let mut executor = self.executor();
+
+        for (trigger, event) in self.gestures.expire(Instant::now()) {
+            self.execute_gesture_binds(&mut executor, &event, trigger)?;
+        }
+
+        self.macros.advance(&mut executor)?;
+        self.motions.advance(&mut executor)?;
+        self.glides.advance(&mut executor)?;
+        self.commands.advance();
+
+        let limits = self.config.get_limit_config();
+        for (bind, event) in self.repeater.due() {
+            if self.limiter.try_repeat(&limits) {
+                self.execute_momentary(&mut executor, &bind, &event)?;
+            }
+        }
+
+        for (event, idx) in self.watchdog.expired() {
+            eprintln!(
+                "Warning: {} was held on bind {} for longer than allowed, so it's been released in case the note's end was lost",
+                event.note,
+                idx + 1
+            );
+
+            self.release_stuck_bind(&mut executor, &event, idx)?;
+        }
+
+        Ok(())
+    }
+
+    /// Release the bind at the given index as if its note had ended, ignoring the sustain pedal, for when the note's end
+    /// seems to have been lost. The note's other binds are left as they are
+    fn release_stuck_bind(
+        &mut self,
+        executor: &mut Executor,
+        event: &NoteEvent,
+        idx: usize,
+    ) -> anyhow::Result<()> {
+        self.repeater.stop_bind(&event.note, idx);
+
+        let (stuck, ended) = self.pressed_binds.take_bind(&event.note, idx);
+
+        // Once nothing the note started is left, it's treated as having ended
+        if ended {
+            self.input.release(&event.note);
+            self.repeater.stop(&event.note);
+        }
+
+        for bind in stuck {
+            self.release_bind(executor, &bind, event)?;
+        }
+
+        Ok(())
+    }
+
     /// Updates which bind is currently being edited in the GUI, so
     /// we can access it more easily without first having to know anything about it
     fn set_active_bind(&mut self, idx: Option<usize>) {
@@ -98,7 +949,7 @@ impl State {
     }
 
     /// Delete the current active bind, if there is one set, returning it's previous index
-    fn delete_active_bind(&mut self) -> Option<usize> {
+    fn delete_active_bind(&mut self) -> anyhow::Result<Option<usize>> {
         if let Some(idx) = self.active_bind {
             // Delete bind
             self.config
@@ -108,11 +959,29 @@ impl State {
             // Unset active bind
             self.set_active_bind(None);
 
+            // The indexes of later binds have changed
+            self.limiter.forget_binds();
+            self.choices.bind_deleted(idx);
+            self.repeater.bind_deleted(idx);
+            self.watchdog.bind_deleted(idx);
+
+            self.pressed_binds.bind_deleted(idx);
+
+            // A deleted bind can't be unlatched any more, and later latched binds move down to their new indexes
+            if let Some((event, bind)) = self.latched_binds.remove(&idx) {
+                let mut executor = self.executor();
+                self.release_bind(&mut executor, &bind, &event)?;
+            }
+            self.latched_binds = std::mem::take(&mut self.latched_binds)
+                .into_iter()
+                .map(|(x, latched)| (if x > idx { x - 1 } else { x }, latched))
+                .collect();
+
             // Return old index of the deleted bind
-            return Some(idx);
+            return Ok(Some(idx));
         }
 
-        None
+        Ok(None)
     }
 
     /// Set the current active bind, if there is one, to the given bind
@@ -123,6 +992,9 @@ impl State {
                 .set_bind(idx, bind)
                 .expect("active bind shouldn't be out-of-bounds");
 
+            // An edited cycle starts again from wherever it was saved at
+            self.choices.forget(idx);
+
             // Return it's index
             return Some(idx);
         }
@@ -0,0 +1,196 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    bind::{BindExecuteState, Executor, NoteEvent},
+    config::{
+        BindAction, ContinuousMotion, ContinuousMoveBindAction, ContinuousScrollBindAction,
+        RelativePos2D, ScrollBindAction, ScrollDirection,
+    },
+    note::Note,
+};
+
+/// Fastest a continuous action can accelerate to, as a multiple of its starting speed
+const MAX_SPEEDUP: f64 = 10.0;
+
+/// What a continuous action moves
+#[derive(Clone, Copy)]
+enum MotionKind {
+    Mouse,
+    Scroll,
+}
+
+/// A continuous action in progress, while its note is held
+struct Motion {
+    /// The note event which started the motion
+    event: NoteEvent,
+    kind: MotionKind,
+    /// How far to move along each axis each time, at the starting speed
+    step: (f64, f64),
+    /// How often and how quickly to move
+    config: ContinuousMotion,
+    /// When the motion started, to accelerate from
+    started: Instant,
+    /// When the next movement is due
+    next_at: Instant,
+    /// Movement too small to have happened yet, carried over to the next time
+    remainder: (f64, f64),
+}
+
+impl Motion {
+    /// Move once, by however far the motion has sped up to
+    fn tick(&mut self, executor: &mut Executor, now: Instant) -> anyhow::Result<()> {
+        let mut speed = 1.0
+            + f64::from(self.config.acceleration) / 100.0
+                * now.duration_since(self.started).as_secs_f64();
+        speed = speed.min(MAX_SPEEDUP);
+
+        if self.config.velocity_sensitive {
+            // A medium velocity moves at the configured speed
+            speed *= f64::from(self.event.vel) / 64.0;
+        }
+
+        self.remainder.0 += self.step.0 * speed;
+        self.remainder.1 += self.step.1 * speed;
+
+        // Always small enough to fit, as steps are `i32`s and the speedup is limited
+        #[allow(clippy::cast_possible_truncation)]
+        let (x, y) = (
+            self.remainder.0.trunc() as i32,
+            self.remainder.1.trunc() as i32,
+        );
+        self.remainder.0 -= f64::from(x);
+        self.remainder.1 -= f64::from(y);
+
+        let actions = match self.kind {
+            MotionKind::Mouse if x != 0 || y != 0 => {
+                vec![BindAction::MoveMouseRelative(RelativePos2D { x, y })]
+            }
+            MotionKind::Mouse => vec![],
+            MotionKind::Scroll => [(ScrollDirection::Right, x), (ScrollDirection::Down, y)]
+                .into_iter()
+                .filter(|(_, amount)| *amount != 0)
+                .map(|(direction, amount)| {
+                    BindAction::Scroll(ScrollBindAction {
+                        direction,
+                        amount,
+                        precise: false,
+                    })
+                })
+                .collect(),
+        };
+
+        for action in actions {
+            executor.execute_action(&action, &self.event, &BindExecuteState::Begin)?;
+        }
+
+        let interval = Duration::from_millis(self.config.interval_ms.max(1).into());
+        self.next_at += interval;
+
+        // Rather than catching up all at once after falling behind, carry on from now
+        if self.next_at < now {
+            self.next_at = now + interval;
+        }
+
+        Ok(())
+    }
+}
+
+/// Performs continuous mouse movement and scrolling while notes are held
+#[derive(Default)]
+pub struct MotionRunner {
+    /// Motions in progress
+    motions: Vec<Motion>,
+}
+
+impl MotionRunner {
+    /// Start moving the mouse continuously, straight away moving for the first time
+    pub fn start_move(
+        &mut self,
+        executor: &mut Executor,
+        action: &ContinuousMoveBindAction,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        self.start(
+            executor,
+            MotionKind::Mouse,
+            (f64::from(action.step.x), f64::from(action.step.y)),
+            &action.motion,
+            event,
+        )
+    }
+
+    /// Start scrolling continuously, straight away scrolling for the first time
+    pub fn start_scroll(
+        &mut self,
+        executor: &mut Executor,
+        action: &ContinuousScrollBindAction,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        // Precise amounts are hundredths of a line, which build up between scrolls like any other remainder
+        let mut amount = f64::from(action.step.amount);
+        if action.step.precise {
+            amount /= 100.0;
+        }
+
+        let step = match action.step.direction {
+            ScrollDirection::Up => (0.0, -amount),
+            ScrollDirection::Down => (0.0, amount),
+            ScrollDirection::Left => (-amount, 0.0),
+            ScrollDirection::Right => (amount, 0.0),
+        };
+
+        self.start(executor, MotionKind::Scroll, step, &action.motion, event)
+    }
+
+    fn start(
+        &mut self,
+        executor: &mut Executor,
+        kind: MotionKind,
+        step: (f64, f64),
+        motion: &ContinuousMotion,
+        event: &NoteEvent,
+    ) -> anyhow::Result<()> {
+        let now = Instant::now();
+
+        let mut motion = Motion {
+            event: event.clone(),
+            kind,
+            step,
+            config: motion.clone(),
+            started: now,
+            next_at: now,
+            remainder: (0.0, 0.0),
+        };
+
+        motion.tick(executor, now)?;
+        self.motions.push(motion);
+
+        Ok(())
+    }
+
+    /// Stop the motions started by the given note
+    pub fn stop(&mut self, note: &Note) {
+        self.motions.retain(|x| x.event.note != *note);
+    }
+
+    /// Returns the soonest time a motion's next movement is due, if any are in progress
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.motions.iter().map(|x| x.next_at).min()
+    }
+
+    /// Move each motion which is due to by now
+    pub fn advance(&mut self, executor: &mut Executor) -> anyhow::Result<()> {
+        let now = Instant::now();
+
+        for motion in self.motions.iter_mut().filter(|x| x.next_at <= now) {
+            motion.tick(executor, now)?;
+        }
+
+        Ok(())
+    }
+
+    /// Stop every motion
+    pub fn clear(&mut self) {
+        self.motions.clear();
+    }
+}
@@ -0,0 +1,194 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::{config::Bind, note::Note};
+
+/// Binds started by each held note, with their indexes, so the same ones are released when it ends
+#[derive(Default)]
+pub struct PressedBinds {
+    pressed: HashMap<Note, Vec<(usize, Bind)>>,
+}
+
+impl PressedBinds {
+    /// Record the binds a note has just started.
+    ///
+    /// Any binds still recorded for an earlier press of the note are kept alongside them, so they're released when the
+    /// note ends rather than being forgotten while held. A press which started nothing, such as one which was
+    /// debounced, leaves the earlier press as it was
+    pub fn start(&mut self, note: &Note, binds: Vec<(usize, Bind)>) {
+        if binds.is_empty() {
+            return;
+        }
+
+        self.pressed.entry(note.clone()).or_default().extend(binds);
+    }
+
+    /// Returns every bind the note started, with their indexes, no longer recording them as it's ended
+    pub fn end(&mut self, note: &Note) -> Vec<(usize, Bind)> {
+        self.pressed.remove(note).unwrap_or_default()
+    }
+
+    /// Returns the binds matching the given check which were started by notes other than the given one, along with
+    /// the notes which started them, no longer recording them
+    pub fn take_matching(
+        &mut self,
+        except: &Note,
+        check: impl Fn(&Bind) -> bool,
+    ) -> Vec<(Note, Bind)> {
+        let mut taken = vec![];
+
+        for (note, binds) in &mut self.pressed {
+            if note == except {
+                continue;
+            }
+
+            binds.retain(|(_, x)| {
+                if check(x) {
+                    taken.push((note.clone(), x.clone()));
+                    return false;
+                }
+
+                true
+            });
+        }
+
+        taken
+    }
+
+    /// Returns the bind at the given index if the note started it, no longer recording it. The note's other binds are
+    /// left as they are, unless none are left, in which case the note is forgotten and the second value is true
+    pub fn take_bind(&mut self, note: &Note, idx: usize) -> (Vec<Bind>, bool) {
+        let Some(binds) = self.pressed.get_mut(note) else {
+            return (vec![], false);
+        };
+
+        let (taken, still_pressed): (Vec<_>, Vec<_>) = std::mem::take(binds)
+            .into_iter()
+            .partition(|(x, _)| *x == idx);
+        *binds = still_pressed;
+
+        let ended = binds.is_empty();
+        if ended {
+            self.pressed.remove(note);
+        }
+
+        (taken.into_iter().map(|(_, x)| x).collect(), ended)
+    }
+
+    /// Update the indexes of pressed binds as the bind at the given index has been deleted. If it's pressed, it's
+    /// still released when its note ends, but can't be found by its index any more
+    pub fn bind_deleted(&mut self, idx: usize) {
+        for (x, _) in self.pressed.values_mut().flatten() {
+            *x = match (*x).cmp(&idx) {
+                Ordering::Less => *x,
+                Ordering::Equal => usize::MAX,
+                Ordering::Greater => *x - 1,
+            };
+        }
+    }
+
+    /// Forget every pressed bind
+    pub fn clear(&mut self) {
+        self.pressed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::{Bind, BindAction, KeyboardKeyBindAction},
+        held::{HeldInput, HeldInputs},
+        key::{Key, KeyCombo},
+        note::Note,
+    };
+
+    use super::PressedBinds;
+
+    /// Returns a bind holding down the given key
+    fn hold_key(key: char) -> Bind {
+        Bind {
+            action: BindAction::HoldKey(KeyboardKeyBindAction {
+                key: KeyCombo {
+                    modifiers: vec![],
+                    key: Key::Char(key),
+                },
+            }),
+            ..Bind::default()
+        }
+    }
+
+    /// Returns what the bind holds down
+    fn input(bind: &Bind) -> HeldInput {
+        let BindAction::HoldKey(param) = &bind.action else {
+            unreachable!();
+        };
+        HeldInput::Key(param.key.key.clone())
+    }
+
+    /// Start a note the same way the state does, where a press which acts releases what the note started before
+    fn note_on(pressed: &mut PressedBinds, held: &HeldInputs, note: &Note, acting: Vec<Bind>) {
+        if !acting.is_empty() {
+            for (_, bind) in pressed.end(note) {
+                held.release(&input(&bind), note);
+            }
+        }
+
+        for bind in &acting {
+            held.press(input(bind), note);
+        }
+
+        pressed.start(note, acting.into_iter().enumerate().collect());
+    }
+
+    /// End a note the same way the state does
+    fn note_off(pressed: &mut PressedBinds, held: &HeldInputs, note: &Note) {
+        for (_, bind) in pressed.end(note) {
+            held.release(&input(&bind), note);
+        }
+    }
+
+    #[test]
+    fn releases_everything_after_note_started_twice() {
+        let (mut pressed, held) = (PressedBinds::default(), HeldInputs::default());
+        let note = Note::from_midi(60);
+
+        note_on(
+            &mut pressed,
+            &held,
+            &note,
+            vec![hold_key('a'), hold_key('b')],
+        );
+        note_on(&mut pressed, &held, &note, vec![hold_key('a')]);
+        note_off(&mut pressed, &held, &note);
+
+        assert!(held.holds().is_empty());
+    }
+
+    #[test]
+    fn keeps_earlier_press_when_nothing_acts() {
+        let (mut pressed, held) = (PressedBinds::default(), HeldInputs::default());
+        let note = Note::from_midi(60);
+
+        note_on(&mut pressed, &held, &note, vec![hold_key('a')]);
+        // Debounced, so nothing acts
+        note_on(&mut pressed, &held, &note, vec![]);
+        assert_eq!(held.holds().len(), 1);
+
+        note_off(&mut pressed, &held, &note);
+        assert!(held.holds().is_empty());
+    }
+
+    #[test]
+    fn forgets_note_once_its_last_bind_is_taken() {
+        let mut pressed = PressedBinds::default();
+        let note = Note::from_midi(60);
+        pressed.start(&note, vec![(0, hold_key('a')), (2, hold_key('b'))]);
+
+        pressed.bind_deleted(1);
+        assert_eq!(pressed.take_bind(&note, 0).0.len(), 1);
+
+        let (taken, ended) = pressed.take_bind(&note, 1);
+        assert_eq!(taken.len(), 1);
+        assert!(ended);
+        assert!(pressed.end(&note).is_empty());
+    }
+}
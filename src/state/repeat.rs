@@ -0,0 +1,92 @@
+use std::time::{Duration, Instant};
+
+use crate::{bind::NoteEvent, config::Bind, note::Note};
+
+/// A bind being repeated while its note is held
+struct Repeat {
+    /// Index of the bind, to stop it alone
+    idx: usize,
+    bind: Bind,
+    /// The note event which started the bind
+    event: NoteEvent,
+    /// When the next repeat is due
+    next_at: Instant,
+}
+
+/// Repeats the actions of binds while their notes are held, like a held key on a keyboard
+#[derive(Default)]
+pub struct AutoRepeater {
+    /// Binds being repeated
+    repeats: Vec<Repeat>,
+}
+
+impl AutoRepeater {
+    /// Start repeating the bind at the given index which has just acted, if it's set to repeat
+    pub fn start(&mut self, idx: usize, bind: &Bind, event: &NoteEvent) {
+        let Some(auto_repeat) = &bind.auto_repeat else {
+            return;
+        };
+
+        if !bind.action.can_repeat() {
+            return;
+        }
+
+        self.repeats.push(Repeat {
+            idx,
+            bind: bind.clone(),
+            event: event.clone(),
+            next_at: Instant::now() + Duration::from_millis(auto_repeat.delay_ms.into()),
+        });
+    }
+
+    /// Stop repeating the binds of the given note
+    pub fn stop(&mut self, note: &Note) {
+        self.repeats.retain(|x| x.event.note != *note);
+    }
+
+    /// Stop repeating the bind at the given index for the given note, leaving the note's other binds repeating
+    pub fn stop_bind(&mut self, note: &Note, idx: usize) {
+        self.repeats
+            .retain(|x| x.event.note != *note || x.idx != idx);
+    }
+
+    /// Stop repeating the bind at the given index as it's been deleted, moving later binds down to their new indexes
+    pub fn bind_deleted(&mut self, idx: usize) {
+        self.repeats.retain(|x| x.idx != idx);
+
+        for repeat in &mut self.repeats {
+            if repeat.idx > idx {
+                repeat.idx -= 1;
+            }
+        }
+    }
+
+    /// Returns the soonest time a bind is due to repeat, if any are repeating
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.repeats.iter().map(|x| x.next_at).min()
+    }
+
+    /// Returns the bind and starting event of every bind which is due to repeat by now, scheduling their next repeat
+    pub fn due(&mut self) -> Vec<(Bind, NoteEvent)> {
+        let now = Instant::now();
+
+        self.repeats
+            .iter_mut()
+            .filter(|x| x.next_at <= now)
+            .map(|x| {
+                let interval = x.bind.auto_repeat.as_ref().map_or(0, |x| x.interval_ms);
+                let interval = Duration::from_millis(interval.max(1).into());
+
+                // Rather than catching up all at once after falling behind, carry on from now
+                x.next_at = (x.next_at + interval).max(now);
+
+                (x.bind.clone(), x.event.clone())
+            })
+            .collect()
+    }
+
+    /// Stop repeating everything
+    pub fn clear(&mut self) {
+        self.repeats.clear();
+    }
+}
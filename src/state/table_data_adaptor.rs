@@ -19,7 +19,7 @@ impl Adaptor {
 
 impl TableDataSource for Adaptor {
     fn num_columns(&mut self) -> i32 {
-        2
+        5
     }
 
     fn num_rows(&mut self) -> i32 {
@@ -28,8 +28,8 @@ impl TableDataSource for Adaptor {
 
     fn column_type(&mut self, column: i32) -> libui::controls::TableValueType {
         match column {
-            0 | 1 => TableValueType::String,
-            _ => unreachable!("shouldn't be more than two columns"),
+            0..=4 => TableValueType::String,
+            _ => unreachable!("shouldn't be more than five columns"),
         }
     }
 
@@ -55,7 +55,35 @@ impl TableDataSource for Adaptor {
                     ),
                 }
             }
-            _ => unreachable!("shouldn't be more than two columns"),
+            2 => {
+                // How the bind is triggered
+                match self.interface.get_bind_trigger_string(row) {
+                    Ok(x) => libui::controls::TableValue::String(x),
+                    Err(_) => unreachable!(
+                        "binds table row index shouldn't be out of bounds of the vec<bind>"
+                    ),
+                }
+            }
+            3 => {
+                // The layer the bind is in
+                match self.interface.get_bind_layer_string(row) {
+                    Ok(x) => libui::controls::TableValue::String(x),
+                    Err(_) => unreachable!(
+                        "binds table row index shouldn't be out of bounds of the vec<bind>"
+                    ),
+                }
+            }
+            4 => {
+                // Whether the bind is toggled on
+                libui::controls::TableValue::String(
+                    if self.interface.get_latched_binds().contains(&row) {
+                        "Latched".into()
+                    } else {
+                        String::new()
+                    },
+                )
+            }
+            _ => unreachable!("shouldn't be more than five columns"),
         }
     }
 
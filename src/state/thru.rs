@@ -0,0 +1,205 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use midly::{live::LiveEvent, num::u7, MidiMessage};
+
+use crate::{config::ThruRoute, note::Note};
+
+/// Forwards incoming MIDI on to other outputs, keeping track of which notes acted on binds so they can be left out
+#[derive(Default)]
+pub struct Thru {
+    /// Incoming notes which something is bound to, until they end
+    bound_notes: HashSet<Note>,
+}
+
+impl Thru {
+    /// Record that an incoming note, before the input transform, has something bound to it
+    pub fn note_bound(&mut self, note: &Note) {
+        self.bound_notes.insert(note.clone());
+    }
+
+    /// Returns the messages to send for an incoming MIDI message, along with the output port to send each to,
+    /// given the routes and the name of the input port it came from
+    pub fn route(
+        &mut self,
+        routes: &[ThruRoute],
+        input: Option<&str>,
+        bytes: &[u8],
+    ) -> Vec<(Option<String>, Vec<u8>)> {
+        let Ok(event) = LiveEvent::parse(bytes) else {
+            return vec![];
+        };
+
+        let note = match event {
+            LiveEvent::Midi {
+                message:
+                    MidiMessage::NoteOn { key, .. }
+                    | MidiMessage::NoteOff { key, .. }
+                    | MidiMessage::Aftertouch { key, .. },
+                ..
+            } => Some(Note::from_midi(key.as_int())),
+            _ => None,
+        };
+        let bound = note.as_ref().is_some_and(|x| self.bound_notes.contains(x));
+
+        let messages = routes
+            .iter()
+            .filter(|x| x.input.is_none() || x.input.as_deref() == input)
+            .filter(|x| !(bound && x.skip_bound_notes))
+            .filter_map(|route| Some((route.output.clone(), route_event(route, event)?)))
+            .collect();
+
+        // Forget bound notes once they've ended, so they're forwarded again if they're unbound
+        let ended = match event {
+            LiveEvent::Midi {
+                message: MidiMessage::NoteOff { .. },
+                ..
+            } => true,
+            LiveEvent::Midi {
+                message: MidiMessage::NoteOn { vel, .. },
+                ..
+            } => vel.as_int() == 0,
+            _ => false,
+        };
+        if let (true, Some(note)) = (ended, note) {
+            self.bound_notes.remove(&note);
+        }
+
+        messages
+    }
+
+    /// Forget every bound note
+    pub fn clear(&mut self) {
+        self.bound_notes.clear();
+    }
+}
+
+/// Which incoming MIDI messages could be forwarded by any route, so the rest can be dropped before they're ever sent to
+/// the state. Without this, every message from a busy controller, such as its clock, would wait on the state.
+///
+/// Clones share the same routes, as they're kept up to date by the state while the MIDI input checks them
+#[derive(Clone, Default)]
+pub struct ThruFilter {
+    /// Whether there are any routes, and whether any of them forward system messages
+    wanted: Arc<Mutex<(bool, bool)>>,
+}
+
+impl ThruFilter {
+    /// Remember what the given routes could forward, as they've changed
+    pub fn routes_changed(&self, routes: &[ThruRoute]) {
+        *self.wanted.lock().unwrap() =
+            (!routes.is_empty(), routes.iter().any(|x| x.messages.system));
+    }
+
+    /// Returns true if any route could forward the given message
+    pub fn forwards(&self, bytes: &[u8]) -> bool {
+        let (any, system) = *self.wanted.lock().unwrap();
+
+        // System messages, such as clock and active sensing, start at 0xF0 and are the same on every channel
+        let is_system = bytes.first().is_some_and(|x| *x >= 0xF0);
+
+        any && (system || !is_system)
+    }
+}
+
+/// Returns the bytes of an event after it's been through a route, or `None` if the route doesn't let it through
+fn route_event(route: &ThruRoute, event: LiveEvent) -> Option<Vec<u8>> {
+    let event = match event {
+        LiveEvent::Midi { channel, message } => {
+            if !route.channels.is_empty() && !route.channels.contains(&channel.as_int()) {
+                return None;
+            }
+
+            let allowed = match message {
+                MidiMessage::NoteOn { .. } | MidiMessage::NoteOff { .. } => route.messages.notes,
+                MidiMessage::Aftertouch { .. } | MidiMessage::ChannelAftertouch { .. } => {
+                    route.messages.aftertouch
+                }
+                MidiMessage::Controller { .. } => route.messages.control_changes,
+                MidiMessage::ProgramChange { .. } => route.messages.program_changes,
+                MidiMessage::PitchBend { .. } => route.messages.pitch_bend,
+            };
+            if !allowed {
+                return None;
+            }
+
+            // Notes out of range are left out, and so are those transposed out of range
+            let transpose = |key: u7| -> Option<u7> {
+                if !(route.lowest_note..=route.highest_note).contains(&key.as_int()) {
+                    return None;
+                }
+
+                let key = i16::from(key.as_int()) + i16::from(route.transpose);
+                u7::try_from(u8::try_from(key).ok()?)
+            };
+
+            let message = match message {
+                MidiMessage::NoteOn { key, vel } => MidiMessage::NoteOn {
+                    key: transpose(key)?,
+                    vel,
+                },
+                MidiMessage::NoteOff { key, vel } => MidiMessage::NoteOff {
+                    key: transpose(key)?,
+                    vel,
+                },
+                MidiMessage::Aftertouch { key, vel } => MidiMessage::Aftertouch {
+                    key: transpose(key)?,
+                    vel,
+                },
+                x => x,
+            };
+
+            LiveEvent::Midi {
+                channel: route.output_channel.map_or(channel, Into::into),
+                message,
+            }
+        }
+        x if route.messages.system => x,
+        _ => return None,
+    };
+
+    let mut bytes = vec![];
+    event.write_std(&mut bytes).ok()?;
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{ThruMessages, ThruRoute};
+
+    use super::ThruFilter;
+
+    const CLOCK: [u8; 1] = [0xF8];
+    const NOTE_ON: [u8; 3] = [0x90, 60, 100];
+
+    #[test]
+    fn forwards_nothing_without_routes() {
+        let filter = ThruFilter::default();
+
+        assert!(!filter.forwards(&NOTE_ON));
+        assert!(!filter.forwards(&CLOCK));
+    }
+
+    #[test]
+    fn forwards_clock_only_to_routes_wanting_system_messages() {
+        let filter = ThruFilter::default();
+        let mut route = ThruRoute {
+            messages: ThruMessages {
+                system: false,
+                ..ThruMessages::default()
+            },
+            ..ThruRoute::default()
+        };
+
+        filter.routes_changed(&[route.clone()]);
+        assert!(filter.forwards(&NOTE_ON));
+        assert!(!filter.forwards(&CLOCK));
+
+        route.messages.system = true;
+        filter.routes_changed(&[route]);
+        assert!(filter.forwards(&CLOCK));
+    }
+}
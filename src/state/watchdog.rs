@@ -0,0 +1,58 @@
+use std::time::{Duration, Instant};
+
+use crate::{bind::NoteEvent, note::Note};
+
+/// Releases binds which have been held for too long, in case the message ending their note was lost
+#[derive(Default)]
+pub struct HoldWatchdog {
+    /// Binds being watched, by the event which started them and their index, with when they'll be released
+    watched: Vec<(NoteEvent, usize, Instant)>,
+}
+
+impl HoldWatchdog {
+    /// Start watching a bind its note has just started, releasing it once it's been held for the given time
+    pub fn start(&mut self, event: &NoteEvent, idx: usize, max_hold: Duration) {
+        self.watched
+            .retain(|(x, watched_idx, _)| x.note != event.note || *watched_idx != idx);
+        self.watched
+            .push((event.clone(), idx, Instant::now() + max_hold));
+    }
+
+    /// Stop watching the binds of the given note, as it's ended
+    pub fn stop(&mut self, note: &Note) {
+        self.watched.retain(|(x, _, _)| x.note != *note);
+    }
+
+    /// Returns the soonest time a bind will have been held for too long, if any are being watched
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.watched.iter().map(|(_, _, x)| *x).min()
+    }
+
+    /// Returns the starting event and index of every bind held for too long by now, no longer watching them
+    pub fn expired(&mut self) -> Vec<(NoteEvent, usize)> {
+        let now = Instant::now();
+
+        let (expired, watched) = std::mem::take(&mut self.watched)
+            .into_iter()
+            .partition(|(_, _, x)| *x <= now);
+        self.watched = watched;
+
+        expired.into_iter().map(|(x, idx, _)| (x, idx)).collect()
+    }
+
+    /// Stop watching the bind at the given index as it's been deleted, moving later binds down to their new indexes
+    pub fn bind_deleted(&mut self, idx: usize) {
+        self.watched.retain(|(_, x, _)| *x != idx);
+
+        for (_, x, _) in &mut self.watched {
+            if *x > idx {
+                *x -= 1;
+            }
+        }
+    }
+
+    /// Stop watching every bind
+    pub fn clear(&mut self) {
+        self.watched.clear();
+    }
+}
@@ -0,0 +1,555 @@
+//! A short textual syntax for actions, used where several actions are written in one place, such as the steps of a macro.
+//!
+//! Each action is written as a word followed by its parameters, like `press Ctrl+C`, `click right`,
+//! `move 10 -5`, `moveto 100 200`, `clickat 100 200 right`, `drag 0 0 100 200`, `scroll down 3` or `text Hello, world!`.
+
+use anyhow::{anyhow, bail};
+
+use crate::{
+    config::{
+        AbsolutePos2D, BindAction, ClickAtBindAction, DragBindAction, Glide, KeyboardKeyBindAction,
+        MacroStep, MonitorChoice, MonitorPos2D, MouseButton, RelativePos2D, ScrollBindAction,
+        ScrollDirection, TextBindAction, WeightedAction,
+    },
+    key::KeyCombo,
+    text::parse_text,
+};
+
+impl TryFrom<&str> for BindAction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim_start();
+        let (word, rest) = value.split_once(' ').unwrap_or((value, ""));
+
+        // Text is taken as-is, but other parameters can be spaced out however
+        if word.eq_ignore_ascii_case("text") {
+            parse_text(rest)?;
+
+            return Ok(BindAction::Text(TextBindAction {
+                text: rest.into(),
+                char_delay_ms: 0,
+            }));
+        }
+
+        let params: Vec<&str> = rest.split_whitespace().collect();
+
+        Ok(match word.to_lowercase().as_str() {
+            "press" => BindAction::PressKey(KeyboardKeyBindAction {
+                key: KeyCombo::try_from(rest)?,
+            }),
+            "hold" => BindAction::HoldKey(KeyboardKeyBindAction {
+                key: KeyCombo::try_from(rest)?,
+            }),
+            "click" => BindAction::Click(mouse_button(&params)?),
+            "holdclick" => BindAction::HoldClick(mouse_button(&params)?),
+            "move" => {
+                let (x, y) = numbers(&params)?;
+                BindAction::MoveMouseRelative(RelativePos2D { x, y })
+            }
+            "moveto" => {
+                let (position, rest) = position(&params)?;
+                if !rest.is_empty() {
+                    bail!("expected only a position");
+                }
+
+                BindAction::MoveMouseAbsolute(position)
+            }
+            "clickat" => {
+                let (position, button) = position(&params)?;
+
+                BindAction::ClickAt(ClickAtBindAction {
+                    button: mouse_button(button)?,
+                    position,
+                    return_cursor: true,
+                })
+            }
+            "drag" => {
+                let (from, rest) = position(&params)?;
+                let (to, button) = position(rest)?;
+
+                // Steps happen all at once, so there's no time to glide in
+                BindAction::Drag(DragBindAction {
+                    button: mouse_button(button)?,
+                    from,
+                    to,
+                    glide: Glide {
+                        duration_ms: 0,
+                        ..Default::default()
+                    },
+                })
+            }
+            "scroll" => {
+                let [direction, amount] = params[..] else {
+                    bail!("expected a direction and an amount to scroll by");
+                };
+
+                // Amounts with a decimal point are scrolled precisely, in hundredths of a line
+                let precise = amount.contains('.');
+                let amount = if precise {
+                    let lines: f64 = amount
+                        .parse()
+                        .map_err(|_| anyhow!("`{amount}` isn't a number"))?;
+
+                    // Saturates if it's too big to fit, which is far more than could be scrolled anyway
+                    #[allow(clippy::cast_possible_truncation)]
+                    let hundredths = (lines * 100.0).round() as i32;
+                    hundredths
+                } else {
+                    amount
+                        .parse()
+                        .map_err(|_| anyhow!("`{amount}` isn't a number"))?
+                };
+
+                BindAction::Scroll(ScrollBindAction {
+                    direction: match direction.to_lowercase().as_str() {
+                        "up" => ScrollDirection::Up,
+                        "down" => ScrollDirection::Down,
+                        "left" => ScrollDirection::Left,
+                        "right" => ScrollDirection::Right,
+                        _ => bail!("`{direction}` isn't a direction to scroll in"),
+                    },
+                    amount,
+                    precise,
+                })
+            }
+            "debug" => BindAction::Debug,
+            "macro" => bail!("macros can't contain other macros"),
+            "cycle" | "random" => bail!("`{word}` can't be written as a single action"),
+            _ => bail!("`{word}` isn't an action"),
+        })
+    }
+}
+
+impl BindAction {
+    /// Write the action the way it's parsed, or None if it can't be, such as a command or the timing of a glide, so
+    /// nothing is shown which wouldn't parse back as the same action
+    pub fn to_step(&self) -> Option<String> {
+        Some(match self {
+            BindAction::PressKey(x) => format!("press {}", x.key),
+            BindAction::HoldKey(x) => format!("hold {}", x.key),
+            BindAction::Click(x) => format!("click {}", mouse_button_name(x)),
+            BindAction::HoldClick(x) => format!("holdclick {}", mouse_button_name(x)),
+            BindAction::MoveMouseRelative(x) => format!("move {} {}", x.x, x.y),
+            BindAction::MoveMouseAbsolute(x) => format!("moveto {}", format_position(x)?),
+            BindAction::Scroll(x) => format!(
+                "scroll {} {}",
+                match x.direction {
+                    ScrollDirection::Up => "up",
+                    ScrollDirection::Down => "down",
+                    ScrollDirection::Left => "left",
+                    ScrollDirection::Right => "right",
+                },
+                // Precise amounts are written with a decimal point, even if they're whole lines
+                if x.precise {
+                    format!("{:.2}", f64::from(x.amount) / 100.0)
+                } else {
+                    x.amount.to_string()
+                }
+            ),
+            // Each step is a line of its own, and typed all at once
+            BindAction::Text(x) if x.char_delay_ms == 0 && !x.text.contains(['\n', '\r']) => {
+                format!("text {}", x.text)
+            }
+            BindAction::ClickAt(x) if x.return_cursor => format!(
+                "clickat {} {}",
+                format_position(&x.position)?,
+                mouse_button_name(&x.button)
+            ),
+            BindAction::Drag(x) if x.glide.duration_ms == 0 => format!(
+                "drag {} {} {}",
+                format_position(&x.from)?,
+                format_position(&x.to)?,
+                mouse_button_name(&x.button)
+            ),
+            BindAction::Debug => "debug".into(),
+            _ => return None,
+        })
+    }
+}
+
+impl TryFrom<&str> for MacroStep {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let trimmed = value.trim();
+        let (word, ms) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+
+        if word.eq_ignore_ascii_case("wait") {
+            return Ok(MacroStep::Wait(ms.trim().parse().map_err(|_| {
+                anyhow!("`{}` isn't a number of milliseconds", ms.trim())
+            })?));
+        }
+
+        Ok(MacroStep::Action(BindAction::try_from(value)?))
+    }
+}
+
+impl MacroStep {
+    /// Write the step the way it's parsed, or None if its action can't be
+    pub fn to_step(&self) -> Option<String> {
+        match self {
+            MacroStep::Action(x) => x.to_step(),
+            MacroStep::Wait(x) => Some(format!("wait {x}")),
+        }
+    }
+}
+
+/// Parse the steps of a macro, written one per line. Blank lines are skipped
+pub fn parse_macro_steps(text: &str) -> anyhow::Result<Vec<MacroStep>> {
+    parse_lines(text)
+}
+
+/// Write the steps of a macro one per line, the opposite of [`parse_macro_steps`], or None if any can't be written
+pub fn format_macro_steps(steps: &[MacroStep]) -> Option<String> {
+    format_lines(steps, MacroStep::to_step)
+}
+
+impl TryFrom<&str> for WeightedAction {
+    type Error = anyhow::Error;
+
+    /// An action, optionally starting with how likely it is to be picked, like `3 press F1`. Otherwise its weight is 1
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // Only trimmed at the start, as the end may be part of some text to type
+        let trimmed = value.trim_start();
+        let (word, rest) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+
+        // Actions are never a number, so a number first is a weight
+        let Ok(weight) = word.parse::<u32>() else {
+            return Ok(WeightedAction {
+                weight: 1,
+                action: BindAction::try_from(value)?,
+            });
+        };
+        if weight == 0 {
+            bail!("a weight of 0 would never be picked");
+        }
+
+        Ok(WeightedAction {
+            weight,
+            action: BindAction::try_from(rest)?,
+        })
+    }
+}
+
+impl WeightedAction {
+    /// Write the choice the way it's parsed, leaving out its weight if it's 1, or None if its action can't be written
+    pub fn to_step(&self) -> Option<String> {
+        let action = self.action.to_step()?;
+
+        Some(if self.weight == 1 {
+            action
+        } else {
+            format!("{} {action}", self.weight)
+        })
+    }
+}
+
+/// Parse a list of actions, written one per line. Blank lines are skipped
+pub fn parse_actions(text: &str) -> anyhow::Result<Vec<BindAction>> {
+    parse_lines(text)
+}
+
+/// Write a list of actions one per line, the opposite of [`parse_actions`], or None if any can't be written
+pub fn format_actions(actions: &[BindAction]) -> Option<String> {
+    format_lines(actions, BindAction::to_step)
+}
+
+/// Parse a list of actions to pick from at random, written one per line with optional weights. Blank lines are skipped
+pub fn parse_weighted_actions(text: &str) -> anyhow::Result<Vec<WeightedAction>> {
+    parse_lines(text)
+}
+
+/// Write a list of actions to pick from at random one per line, the opposite of [`parse_weighted_actions`], or None
+/// if any can't be written
+pub fn format_weighted_actions(choices: &[WeightedAction]) -> Option<String> {
+    format_lines(choices, WeightedAction::to_step)
+}
+
+/// Parse each non-blank line as one thing, saying which line any problem is on
+fn parse_lines<'a, T>(text: &'a str) -> anyhow::Result<Vec<T>>
+where
+    T: TryFrom<&'a str, Error = anyhow::Error>,
+{
+    text.lines()
+        .enumerate()
+        .filter(|(_, x)| !x.trim().is_empty())
+        .map(|(idx, x)| T::try_from(x).map_err(|e| anyhow!("line {}: {e}", idx + 1)))
+        .collect()
+}
+
+/// Write each thing on its own line, or None if any of them can't be written
+fn format_lines<T>(items: &[T], format: impl Fn(&T) -> Option<String>) -> Option<String> {
+    Some(
+        items
+            .iter()
+            .map(format)
+            .collect::<Option<Vec<_>>>()?
+            .join("\n"),
+    )
+}
+
+/// Parse the mouse button given as the only parameter, defaulting to the left button
+fn mouse_button(params: &[&str]) -> anyhow::Result<MouseButton> {
+    Ok(match params {
+        [] => MouseButton::Left,
+        [button] => match button.to_lowercase().as_str() {
+            "left" => MouseButton::Left,
+            "right" => MouseButton::Right,
+            "middle" => MouseButton::Middle,
+            "back" => MouseButton::Back,
+            "forward" => MouseButton::Forward,
+            _ => bail!("`{button}` isn't a mouse button"),
+        },
+        _ => bail!("expected a single mouse button"),
+    })
+}
+
+/// The name a mouse button is written as
+fn mouse_button_name(button: &MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "left",
+        MouseButton::Right => "right",
+        MouseButton::Middle => "middle",
+        MouseButton::Back => "back",
+        MouseButton::Forward => "forward",
+    }
+}
+
+/// Parse a pair of numbers given as the only parameters
+fn numbers(params: &[&str]) -> anyhow::Result<(i32, i32)> {
+    let [x, y] = params[..] else {
+        bail!("expected two numbers");
+    };
+
+    let parse =
+        |x: &str| -> anyhow::Result<i32> { x.parse().map_err(|_| anyhow!("`{x}` isn't a number")) };
+
+    Ok((parse(x)?, parse(y)?))
+}
+
+/// Parse a position from the start of the parameters, returning it along with the parameters after it.
+///
+/// Positions are either two numbers of pixels, or two percentages of a monitor's size like `50% 25%`. Percentages
+/// are of the monitor the cursor is on, unless followed by another like `@primary`, `@2` or `@DP-1`
+fn position<'a, 'b>(params: &'a [&'b str]) -> anyhow::Result<(AbsolutePos2D, &'a [&'b str])> {
+    let (coords, rest) = params.split_at(params.len().min(2));
+
+    let [x, y] = coords[..] else {
+        bail!("expected a position");
+    };
+    if !x.ends_with('%') || !y.ends_with('%') {
+        let (x, y) = numbers(coords)?;
+        return Ok((
+            AbsolutePos2D {
+                x,
+                y,
+                monitor: None,
+            },
+            rest,
+        ));
+    }
+
+    let percent = |x: &str| -> anyhow::Result<f64> {
+        x.trim_end_matches('%')
+            .parse()
+            .map_err(|_| anyhow!("`{x}` isn't a percentage"))
+    };
+
+    let (monitor, rest) = match rest.split_first() {
+        Some((monitor, rest)) if monitor.starts_with('@') => {
+            let monitor = &monitor[1..];
+
+            let monitor = if monitor.eq_ignore_ascii_case("cursor") {
+                MonitorChoice::Cursor
+            } else if monitor.eq_ignore_ascii_case("primary") {
+                MonitorChoice::Primary
+            } else if let Ok(number) = monitor.parse() {
+                MonitorChoice::Numbered(number)
+            } else {
+                MonitorChoice::Named(monitor.into())
+            };
+
+            (monitor, rest)
+        }
+        _ => (MonitorChoice::Cursor, rest),
+    };
+
+    let position = AbsolutePos2D {
+        x: 0,
+        y: 0,
+        monitor: Some(MonitorPos2D {
+            monitor,
+            x_percent: percent(x)?,
+            y_percent: percent(y)?,
+        }),
+    };
+
+    Ok((position, rest))
+}
+
+/// Write a position the way [`position`] parses it, or None if it's on a monitor whose name wouldn't parse back
+fn format_position(position: &AbsolutePos2D) -> Option<String> {
+    let Some(on) = &position.monitor else {
+        return Some(format!("{} {}", position.x, position.y));
+    };
+
+    let monitor = match &on.monitor {
+        MonitorChoice::Cursor => String::new(),
+        MonitorChoice::Primary => " @primary".into(),
+        MonitorChoice::Numbered(x) => format!(" @{x}"),
+        // Names which look like another choice, or are spaced out, would be parsed as something else
+        MonitorChoice::Named(x)
+            if x.is_empty()
+                || x.contains(char::is_whitespace)
+                || x.parse::<usize>().is_ok()
+                || x.eq_ignore_ascii_case("cursor")
+                || x.eq_ignore_ascii_case("primary") =>
+        {
+            return None
+        }
+        MonitorChoice::Named(x) => format!(" @{x}"),
+    };
+
+    Some(format!("{}% {}%{monitor}", on.x_percent, on.y_percent))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        AbsolutePos2D, BindAction, ClickAtBindAction, CommandBindAction, DragBindAction,
+        MacroBindAction, MacroStep, MonitorChoice, MonitorPos2D, TextBindAction, WeightedAction,
+    };
+
+    use super::{format_macro_steps, parse_macro_steps};
+
+    /// Parse an action, then write it back out
+    fn round_trip(step: &str) -> Option<String> {
+        BindAction::try_from(step).unwrap().to_step()
+    }
+
+    /// Returns a position on a monitor with the given name
+    fn on_monitor(name: &str) -> AbsolutePos2D {
+        AbsolutePos2D {
+            x: 0,
+            y: 0,
+            monitor: Some(MonitorPos2D {
+                monitor: MonitorChoice::Named(name.into()),
+                x_percent: 50.0,
+                y_percent: 50.0,
+            }),
+        }
+    }
+
+    #[test]
+    fn round_trips_every_kind_of_step() {
+        for step in [
+            "press Ctrl+Shift+t",
+            "hold Alt",
+            "click right",
+            "holdclick middle",
+            "move 10 -5",
+            "moveto 100 200",
+            "moveto 50% 25%",
+            "moveto 50% 25% @primary",
+            "moveto 50% 25% @2",
+            "moveto 50% 25% @DP-1",
+            "clickat 100 200 back",
+            "drag 0 0 10% 20% @primary forward",
+            "scroll down 3",
+            "scroll left 1.50",
+            "text Hello, {velocity}{Enter}",
+            "debug",
+        ] {
+            assert_eq!(round_trip(step).as_deref(), Some(step));
+        }
+    }
+
+    #[test]
+    fn writes_steps_the_usual_way() {
+        assert_eq!(
+            round_trip("  PRESS ctrl+T").as_deref(),
+            Some("press Ctrl+T")
+        );
+        assert_eq!(round_trip("click").as_deref(), Some("click left"));
+        assert_eq!(
+            round_trip("moveto 50% 25% @cursor").as_deref(),
+            Some("moveto 50% 25%")
+        );
+        assert_eq!(
+            round_trip("scroll up 2.0").as_deref(),
+            Some("scroll up 2.00")
+        );
+    }
+
+    #[test]
+    fn writes_nothing_for_actions_which_would_parse_differently() {
+        let unwritable = [
+            BindAction::Text(TextBindAction {
+                text: "slowly".into(),
+                char_delay_ms: 10,
+            }),
+            BindAction::Text(TextBindAction {
+                text: "two\nlines".into(),
+                char_delay_ms: 0,
+            }),
+            BindAction::ClickAt(ClickAtBindAction {
+                return_cursor: false,
+                ..ClickAtBindAction::default()
+            }),
+            BindAction::Drag(DragBindAction::default()),
+            BindAction::MoveMouseAbsolute(on_monitor("primary")),
+            BindAction::MoveMouseAbsolute(on_monitor("3")),
+            BindAction::MoveMouseAbsolute(on_monitor("My Monitor")),
+            BindAction::MoveMouseAbsolute(on_monitor("")),
+            BindAction::Command(CommandBindAction::default()),
+            BindAction::Macro(MacroBindAction::default()),
+        ];
+
+        for action in unwritable {
+            assert!(action.to_step().is_none(), "{}", action.name());
+        }
+    }
+
+    #[test]
+    fn round_trips_macro_steps() {
+        let steps = "press a\nwait 100\ntext  two spaces ";
+        assert_eq!(
+            format_macro_steps(&parse_macro_steps(steps).unwrap()).as_deref(),
+            Some(steps)
+        );
+
+        let steps = [
+            MacroStep::Wait(5),
+            MacroStep::Action(BindAction::Command(CommandBindAction::default())),
+        ];
+        assert!(format_macro_steps(&steps).is_none());
+    }
+
+    #[test]
+    fn round_trips_weighted_actions() {
+        for step in ["press F1", "3 click right"] {
+            let choice = WeightedAction::try_from(step).unwrap();
+            assert_eq!(choice.to_step().as_deref(), Some(step));
+        }
+
+        assert!(WeightedAction::try_from("0 press F1").is_err());
+    }
+
+    #[test]
+    fn rejects_steps_which_dont_make_sense() {
+        for step in [
+            "macro",
+            "cycle",
+            "jump",
+            "click sideways",
+            "move 1",
+            "moveto 1 2 3",
+            "scroll around 3",
+            "text {",
+            "wait soon",
+        ] {
+            assert!(MacroStep::try_from(step).is_err(), "{step}");
+        }
+    }
+}
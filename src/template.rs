@@ -0,0 +1,68 @@
+//! Placeholders which can be written in the text of some actions, filled in as the action happens.
+//!
+//! `{note}` is the note's MIDI note number, `{note_name}` is its name like `C4`, `{velocity}` is how hard it was
+//! played, `{level}` is the velocity from 0 to 1, `{channel}` is its MIDI channel numbered from 1, `{time}` is the
+//! time of day in UTC like `14:05:09` and `{counter}` counts up by one each time it's filled in.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::bind::NoteEvent;
+
+/// Names of every placeholder, as written between braces
+pub const PLACEHOLDERS: [&str; 7] = [
+    "note",
+    "note_name",
+    "velocity",
+    "level",
+    "channel",
+    "time",
+    "counter",
+];
+
+/// Times `{counter}` has been filled in since bindmidi started
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Fill in the placeholders in some text with details of the given note
+pub fn fill_template(text: &str, event: &NoteEvent) -> String {
+    let mut text = text
+        .replace("{note}", &event.note.get_midi().to_string())
+        .replace("{note_name}", &event.note.to_string())
+        .replace("{velocity}", &event.vel.to_string())
+        .replace("{level}", &(f32::from(event.vel) / 127.0).to_string())
+        .replace("{channel}", &(event.channel + 1).to_string());
+
+    if text.contains("{time}") {
+        text = text.replace("{time}", &time_of_day());
+    }
+
+    // Only counted when it's used, so it goes up one at a time for whatever uses it
+    if text.contains("{counter}") {
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed) + 1;
+        text = text.replace("{counter}", &count.to_string());
+    }
+
+    text
+}
+
+/// Fill in a single placeholder, given its name without braces, with details of the given note
+pub fn fill_placeholder(name: &str, event: &NoteEvent) -> String {
+    fill_template(&format!("{{{name}}}"), event)
+}
+
+/// Returns the current time of day in UTC, like `14:05:09`
+fn time_of_day() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs())
+        % (24 * 60 * 60);
+
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / (60 * 60),
+        secs / 60 % 60,
+        secs % 60
+    )
+}
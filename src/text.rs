@@ -0,0 +1,210 @@
+//! The syntax of text typed by text actions, which can press keys partway through, like `Hello{Enter}{Ctrl+S}`.
+//!
+//! Keys are written between braces in the same way as anywhere else (see [`KeyCombo`]). Braces around the name of a
+//! placeholder, like `{velocity}`, are filled in instead (see [`crate::template`]), and `{{` and `}}` type a brace.
+
+use anyhow::{anyhow, bail};
+
+use crate::{
+    bind::NoteEvent,
+    config::{BindAction, KeyboardKeyBindAction, MacroBindAction, MacroStep, TextBindAction},
+    key::{Key, KeyCombo},
+    template::{fill_placeholder, PLACEHOLDERS},
+};
+
+/// A piece of text to type
+pub enum TextPart {
+    /// Characters typed as they are
+    Text(String),
+    /// The name of a placeholder, typed once it's been filled in
+    Placeholder(String),
+    /// A key pressed along with any modifiers
+    Key(KeyCombo),
+}
+
+/// Split text into the characters, placeholders and keys to type.
+///
+/// Placeholders are filled in afterwards, so braces typed with `{{` and `}}` are never taken as one
+pub fn parse_text(text: &str) -> anyhow::Result<Vec<TextPart>> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(x) = chars.next() {
+        match x {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                current.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                current.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(x) => name.push(x),
+                        None => bail!("`{{{name}` is missing a `}}` at the end"),
+                    }
+                }
+
+                if PLACEHOLDERS.contains(&name.as_str()) {
+                    if !current.is_empty() {
+                        parts.push(TextPart::Text(std::mem::take(&mut current)));
+                    }
+                    parts.push(TextPart::Placeholder(name));
+                    continue;
+                }
+
+                let mut key = KeyCombo::try_from(name.as_str())
+                    .map_err(|e| anyhow!("`{{{name}}}` isn't a key or placeholder: {e}"))?;
+
+                // Letters are pressed as their key, so Ctrl+S isn't taken to mean Ctrl+Shift+S
+                if let Key::Char(x) = key.key {
+                    if !key.modifiers.is_empty() {
+                        key.key = Key::Char(x.to_ascii_lowercase());
+                    }
+                }
+
+                if !current.is_empty() {
+                    parts.push(TextPart::Text(std::mem::take(&mut current)));
+                }
+                parts.push(TextPart::Key(key));
+            }
+            '}' => bail!("a `}}` to type must be written as `}}}}`"),
+            x => current.push(x),
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(TextPart::Text(current));
+    }
+
+    Ok(parts)
+}
+
+/// Make a macro typing a text action's text one character or key at a time, waiting its delay between each,
+/// with its placeholders filled in from the given note
+pub fn typing_macro(action: &TextBindAction, event: &NoteEvent) -> anyhow::Result<MacroBindAction> {
+    let mut steps = vec![];
+
+    for part in parse_text(&action.text)? {
+        let text = match part {
+            TextPart::Text(text) => text,
+            TextPart::Placeholder(name) => fill_placeholder(&name, event),
+            TextPart::Key(key) => {
+                steps.push(MacroStep::Action(BindAction::PressKey(
+                    KeyboardKeyBindAction { key },
+                )));
+                steps.push(MacroStep::Wait(action.char_delay_ms));
+                continue;
+            }
+        };
+
+        for x in text.chars() {
+            // Braces are written twice, so they're typed rather than taken as the start of a key
+            let text = match x {
+                '{' => "{{".into(),
+                '}' => "}}".into(),
+                x => x.to_string(),
+            };
+
+            steps.push(MacroStep::Action(BindAction::Text(TextBindAction {
+                text,
+                char_delay_ms: 0,
+            })));
+            steps.push(MacroStep::Wait(action.char_delay_ms));
+        }
+    }
+
+    // There's nothing to wait for after the last character
+    steps.pop();
+
+    Ok(MacroBindAction {
+        steps,
+        repeat: 0,
+        cancel_on_release: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bind::NoteEvent,
+        config::{BindAction, MacroStep, TextBindAction},
+        note::Note,
+    };
+
+    use super::{parse_text, typing_macro, TextPart};
+
+    /// Parse text, describing each part as its kind followed by what's in it
+    fn parse(text: &str) -> Vec<String> {
+        parse_text(text)
+            .unwrap()
+            .into_iter()
+            .map(|x| match x {
+                TextPart::Text(x) => format!("text {x}"),
+                TextPart::Placeholder(x) => format!("placeholder {x}"),
+                TextPart::Key(x) => format!("key {x}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn types_doubled_braces_as_text() {
+        assert_eq!(parse("{{a}}"), ["text {a}"]);
+        assert_eq!(parse("{{velocity}}"), ["text {velocity}"]);
+        assert_eq!(parse("x{{{Enter}}}"), ["text x{", "key Enter", "text }"]);
+    }
+
+    #[test]
+    fn rejects_lone_and_unterminated_braces() {
+        assert!(parse_text("a}b").is_err());
+        assert!(parse_text("a{Enter").is_err());
+        assert!(parse_text("{").is_err());
+        assert!(parse_text("{Nonsense}").is_err());
+    }
+
+    #[test]
+    fn lowercases_letters_pressed_with_modifiers() {
+        assert_eq!(parse("{Ctrl+S}"), ["key Ctrl+s"]);
+        assert_eq!(parse("{Ctrl+Shift+S}"), ["key Ctrl+Shift+s"]);
+        assert_eq!(parse("{S}"), ["key S"]);
+    }
+
+    #[test]
+    fn tells_placeholders_from_keys() {
+        assert_eq!(
+            parse("Hi {velocity}{Enter}"),
+            ["text Hi ", "placeholder velocity", "key Enter"]
+        );
+        assert_eq!(parse("{Tab}"), ["key Tab"]);
+    }
+
+    #[test]
+    fn types_filled_in_braces_as_text() {
+        let action = TextBindAction {
+            text: "{note}}}".into(),
+            char_delay_ms: 10,
+        };
+        let event = NoteEvent {
+            note: Note::from_midi(60),
+            vel: 100,
+            channel: 0,
+        };
+
+        let typed: Vec<String> = typing_macro(&action, &event)
+            .unwrap()
+            .steps
+            .into_iter()
+            .filter_map(|x| match x {
+                MacroStep::Action(BindAction::Text(x)) => Some(x.text),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(typed, ["6", "0", "}}"]);
+    }
+}